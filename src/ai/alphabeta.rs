@@ -0,0 +1,316 @@
+use super::*;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// The default size of `AlphaBeta::table`.
+const TABLE: usize = 16 << 20;
+
+/// How deep `AlphaBeta` searches without limits.
+const DEFAULT_DEPTH: usize = 6;
+
+/// How deep `AlphaBeta` searches at most.
+const MAX_DEPTH: usize = 64;
+
+/// A negamax search with alpha-beta pruning, deepened iteratively until its
+/// limits are reached.
+#[derive(Clone, Debug)]
+pub struct AlphaBeta {
+    /// The depth completed by the last search.
+    pub depth:     usize,
+    /// The nodes visited by the last search.
+    pub nodes:     u64,
+    /// Kept between searches, so the same deal is not analysed twice.
+    pub table:     Table,
+    /// Exact scores for the positions it covers.
+    pub tablebase: Option<Arc<Tablebase>>,
+    deadline:      Option<Instant>,
+    max_nodes:     Option<u64>,
+    /// Whether the current iteration ran out of time or nodes.
+    aborted:       bool,
+}
+
+impl AlphaBeta {
+    pub fn new() -> Self {
+        Self {
+            depth:     0,
+            nodes:     0,
+            table:     Table::new(TABLE),
+            tablebase: None,
+            deadline:  None,
+            max_nodes: None,
+            aborted:   false,
+        }
+    }
+
+    /// The best play in `game` with its score for the player to move, from
+    /// the last depth completed within `limits`.
+    ///
+    /// The first depth always completes, whatever the limits, so that there
+    /// is a play.
+    pub fn search(&mut self, game: &Game, limits: &Limits) -> (Play, i32) {
+        let start = Instant::now();
+        let mut game = game.clone();
+        let time = limits.time();
+        let max_depth = match limits.depth {
+            Some(depth) => depth.clamp(1, MAX_DEPTH),
+            None if limits.is_empty() => DEFAULT_DEPTH,
+            None => MAX_DEPTH,
+        };
+
+        self.depth = 0;
+        self.nodes = 0;
+        self.deadline = None;
+        self.max_nodes = None;
+
+        let mut best = None;
+        for depth in 1..=max_depth {
+            self.aborted = false;
+            self.negamax(&mut game, depth, 0, -WIN - 1, WIN + 1);
+            if self.aborted {
+                break;
+            }
+
+            let (key, mirrored) = game.canonical_key();
+            let entry = self.table.probe(key).expect("root entry");
+            let play = entry
+                .play
+                .and_then(|play| game.from_canonical(play, mirrored))
+                .expect("game is over");
+
+            best = Some((play, entry.score));
+            self.depth = depth;
+
+            // Nothing changes once the game is solved, and the next depth
+            // would hardly finish in the time left
+            if entry.score.abs() > WIN / 2 || time.is_some_and(|time| start.elapsed() > time / 2) {
+                break;
+            }
+
+            self.deadline = time.map(|time| start + time);
+            self.max_nodes = limits.nodes;
+        }
+
+        best.expect("first depth aborted")
+    }
+
+    /// Whether the search must stop, checking the clock every so often.
+    fn out_of_budget(&self) -> bool {
+        self.max_nodes.is_some_and(|nodes| self.nodes >= nodes)
+            || (self.nodes.is_multiple_of(1024)
+                && self
+                    .deadline
+                    .is_some_and(|deadline| Instant::now() >= deadline))
+    }
+
+    fn negamax(
+        &mut self,
+        game: &mut Game,
+        depth: usize,
+        ply: i32,
+        mut alpha: i32,
+        beta: i32,
+    ) -> i32 {
+        if self.aborted || self.out_of_budget() {
+            self.aborted = true;
+            return 0;
+        }
+        self.nodes += 1;
+
+        let player = match game.state() {
+            State::Turn(player) => player,
+            // Usually won by the previous play, but discarding may lose
+            State::Won(winner) if Some(winner) == game.history().last().map(Record::player) =>
+                return -(WIN - ply),
+            State::Won(_) => return WIN - ply,
+            State::Draw(_) => return 0,
+        };
+
+        // Exact below the root, which searches to find a play (tablebase
+        // values ignore draw rules though)
+        if let Some(value) = self
+            .tablebase
+            .as_ref()
+            .filter(|_| ply > 0)
+            .and_then(|tablebase| tablebase.probe(game))
+        {
+            return match value {
+                Value::Win(plies) => WIN - ply - plies as i32,
+                Value::Loss(plies) => -(WIN - ply - plies as i32),
+                Value::Draw => 0,
+            };
+        }
+
+        if depth == 0 {
+            return evaluate(game, player);
+        }
+
+        let (key, mirrored) = game.canonical_key();
+        let entry = self.table.probe(key);
+
+        if let Some(entry) = entry {
+            let score = from_table(entry.score, ply);
+            let cutoff = match entry.bound {
+                Bound::Exact => true,
+                Bound::Lower => score >= beta,
+                Bound::Upper => score <= alpha,
+            };
+
+            // The root always searches, to find a play
+            if ply > 0 && entry.depth as usize >= depth && cutoff {
+                return score;
+            }
+        }
+
+        let first = entry
+            .and_then(|entry| entry.play)
+            .and_then(|play| game.from_canonical(play, mirrored));
+        let original = alpha;
+        let mut best = (-WIN - 1, None);
+
+        for &play in &ordered(game, first) {
+            game.play(play);
+            let score = -self.negamax(game, depth - 1, ply + 1, -beta, -alpha);
+            game.undo();
+
+            if self.aborted {
+                return 0;
+            }
+            if score > best.0 {
+                best = (score, Some(play));
+            }
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        self.table.store(Entry {
+            key,
+            depth: depth as u8,
+            score: to_table(best.0, ply),
+            bound: if best.0 <= original {
+                Bound::Upper
+            } else if best.0 >= beta {
+                Bound::Lower
+            } else {
+                Bound::Exact
+            },
+            play: best.1.map(|play| game.to_canonical(play, mirrored)),
+        });
+
+        best.0
+    }
+}
+
+impl Default for AlphaBeta {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine for AlphaBeta {
+    fn name(&self) -> String {
+        String::from("alphabeta")
+    }
+
+    fn play(&mut self, game: &Game, limits: &Limits) -> Result<Play, EngineError> {
+        let mut rng = Rng::new(Seed(game.key()));
+        let game = game.determinize(game.player().unwrap(), &mut rng);
+        Ok(self.search(&game, limits).0)
+    }
+}
+
+/// The legal plays of `game`, `first` first then captures.
+fn ordered(game: &Game, first: Option<Play>) -> Plays {
+    let mut plays = game.legal_plays();
+    let player = game.player().unwrap();
+    let is_capture = |play: &Play| match *play {
+        Play::Card { dest, .. } | Play::Wind { dest, .. } =>
+            matches!(game[dest], Some((p, _)) if p != player),
+        _ => false,
+    };
+
+    plays.sort_by_key(|play| (Some(*play) != first, !is_capture(play)));
+    plays
+}
+
+/// Win scores are stored relative to the position, not the root.
+fn to_table(score: i32, ply: i32) -> i32 {
+    if score > WIN / 2 {
+        score + ply
+    } else if score < -WIN / 2 {
+        score - ply
+    } else {
+        score
+    }
+}
+
+fn from_table(score: i32, ply: i32) -> i32 {
+    if score > WIN / 2 {
+        score - ply
+    } else if score < -WIN / 2 {
+        score + ply
+    } else {
+        score
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn game() -> Game {
+        let cards = Card::builtin();
+        let deal = Deal::parse("Tiger,Crab,Monkey,Crane,Dragon", &cards).unwrap();
+        Game::with_cards(cards, deal, Rules::default())
+    }
+
+    #[test]
+    fn depth() {
+        let mut alphabeta = AlphaBeta::new();
+        let limits = Limits {
+            depth: Some(3),
+            ..Limits::default()
+        };
+        alphabeta.search(&game(), &limits);
+
+        assert_eq!(alphabeta.depth, 3);
+    }
+
+    #[test]
+    fn nodes() {
+        let mut alphabeta = AlphaBeta::new();
+        let limits = Limits {
+            nodes: Some(20_000),
+            ..Limits::default()
+        };
+        let (play, score) = alphabeta.search(&game(), &limits);
+
+        assert!(alphabeta.nodes <= 20_000);
+        assert!((1..MAX_DEPTH).contains(&alphabeta.depth));
+
+        // The play and score of the deepest iteration completed
+        let depth = alphabeta.depth;
+        let limits = Limits {
+            depth: Some(depth),
+            ..Limits::default()
+        };
+        assert_eq!(AlphaBeta::new().search(&game(), &limits), (play, score));
+    }
+
+    #[test]
+    fn clock() {
+        let mut alphabeta = AlphaBeta::new();
+        let limits = Limits {
+            clock: Some(Duration::from_secs(4)),
+            ..Limits::default()
+        };
+        let start = Instant::now();
+        alphabeta.search(&game(), &limits);
+
+        // A twentieth of the clock, give or take a check of it
+        assert!(start.elapsed() < Duration::from_millis(300));
+        assert!((1..MAX_DEPTH).contains(&alphabeta.depth));
+    }
+}
@@ -0,0 +1,54 @@
+use super::*;
+
+/// The score of a won game, minus the plies to win.
+pub const WIN: i32 = 1_000_000;
+
+const PAWN: i32 = 100;
+const DISTANCE: i32 = 20;
+const MOBILITY: i32 = 2;
+const MOVE: i32 = 3;
+const FORWARD: i32 = 2;
+
+/// A heuristic score of `game` for `player`: material, king-to-temple
+/// distance, mobility and card quality.
+///
+/// `game` must not be over.
+pub fn evaluate(game: &Game, player: Player) -> i32 {
+    side(game, player) - side(game, !player)
+}
+
+fn side(game: &Game, player: Player) -> i32 {
+    let occupied = game[player].occupied;
+    let king = game[(player, King)].is_some();
+    let pawns = occupied.len() as i32 - king as i32;
+    // No bonus for a king captured without the Way of the Stone
+    let distance = game
+        .distance(player)
+        .map_or(0, |distance| game.size().get() as i32 - 1 - distance as i32);
+
+    let mut mobility = 0;
+    for card in game.cards(player) {
+        for square in occupied.squares() {
+            mobility += (card.dests(player, square, game.size()) & !occupied).len() as i32;
+        }
+    }
+
+    let cards = game
+        .cards(player)
+        .into_iter()
+        .map(card_quality)
+        .sum::<i32>();
+
+    pawns * PAWN + distance * DISTANCE + mobility * MOBILITY + cards
+}
+
+/// How good holding `card` is: its moves, forward ones counting more.
+pub fn card_quality(card: &Card) -> i32 {
+    let forward = card
+        .moves
+        .iter()
+        .filter(|r#move| r#move.offset().1 > 0)
+        .count() as i32;
+
+    card.moves.len() as i32 * MOVE + forward * FORWARD
+}
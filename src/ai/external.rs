@@ -0,0 +1,191 @@
+use super::*;
+use std::io;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Write;
+use std::process::Child;
+use std::process::ChildStdin;
+use std::process::ChildStdout;
+use std::process::Stdio;
+use std::time::Duration;
+use std::time::Instant;
+
+/// How long an engine has to exit after `quit` before it is killed.
+const QUIT_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// An engine running in another process, speaking the Onitama Engine
+/// Interface (see `protocol`).
+///
+/// Plays fail when the engine exits, replies an error or an illegal play.
+#[derive(Debug)]
+pub struct External {
+    name:   String,
+    child:  Child,
+    stdin:  ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    /// The key and rules of the start of the game last sent with `newgame`.
+    start:  Option<(u64, Rules)>,
+}
+
+impl External {
+    /// Starts `command` (a program then its arguments, separated by spaces)
+    /// and opens the session.
+    pub fn spawn(command: &str) -> io::Result<Self> {
+        let mut words = command.split_whitespace();
+        let program = words
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no engine command"))?;
+
+        let mut child = std::process::Command::new(program)
+            .args(words)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let stdin = child.stdin.take().unwrap();
+        let stdout = BufReader::new(child.stdout.take().unwrap());
+
+        let mut engine = Self {
+            name: program.to_string(),
+            child,
+            stdin,
+            stdout,
+            start: None,
+        };
+
+        engine.send(&Command::Oei)?;
+        loop {
+            match engine.receive()? {
+                Reply::Id(name) => engine.name = name,
+                Reply::OeiOk => break,
+                _ => {}
+            }
+        }
+
+        Ok(engine)
+    }
+
+    fn send(&mut self, command: &Command) -> io::Result<()> {
+        writeln!(self.stdin, "{}", command)?;
+        self.stdin.flush()
+    }
+
+    /// The next reply, skipping the lines that are not one.
+    fn receive(&mut self) -> io::Result<Reply> {
+        let mut line = String::new();
+
+        loop {
+            line.clear();
+            if self.stdout.read_line(&mut line)? == 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "engine exited",
+                ));
+            }
+            if let Ok(reply) = line.parse() {
+                return Ok(reply);
+            }
+        }
+    }
+
+    fn search(&mut self, game: &Game, limits: &Limits) -> Result<String, EngineError> {
+        let mut start = game.clone();
+        while start.undo().is_some() {}
+
+        if self.start != Some((start.key(), game.rules())) {
+            let cards = start.card_set();
+            let deal = start.position().deal.cards();
+
+            self.send(&Command::NewGame {
+                cards: deal
+                    .iter()
+                    .map(|&card| cards[card].name.to_string())
+                    .collect(),
+                rules: game.rules(),
+            })?;
+            self.start = Some((start.key(), game.rules()));
+        }
+
+        let position = start.position().to_string();
+        let mut moves = Vec::new();
+        for play in game.history().plays() {
+            moves.push(to_protocol(&start.notation(play)));
+            start.play(play);
+        }
+
+        self.send(&Command::Position {
+            position: Some(position),
+            moves,
+        })?;
+        self.send(&Command::Go(*limits))?;
+
+        loop {
+            match self.receive()? {
+                Reply::BestMove(play) => return Ok(play),
+                Reply::Error(message) => return Err(EngineError::Reply(message)),
+                _ => {}
+            }
+        }
+    }
+}
+
+impl Engine for External {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn play(&mut self, game: &Game, limits: &Limits) -> Result<Play, EngineError> {
+        let mut rng = Rng::new(Seed(game.key()));
+        let seen = game.determinize(game.player().unwrap(), &mut rng);
+        let play = self.search(&seen, limits)?;
+
+        from_protocol(&play)
+            .and_then(|notation| game.play_of(&notation))
+            .map_err(|err| err.to_string())
+            .and_then(|parsed| {
+                game.check(parsed)
+                    .map(|()| parsed)
+                    .map_err(|err| err.to_string())
+            })
+            .map_err(|error| EngineError::Play { play, error })
+    }
+}
+
+impl Drop for External {
+    fn drop(&mut self) {
+        let _ = self.send(&Command::Quit);
+
+        let deadline = Instant::now() + QUIT_TIMEOUT;
+        while let Ok(None) = self.child.try_wait() {
+            if Instant::now() >= deadline {
+                let _ = self.child.kill();
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        let _ = self.child.wait();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn killed_after_quit() {
+        // An engine that ignores `quit`
+        let path = std::env::temp_dir().join(format!("oei-stuck-{}.sh", std::process::id()));
+        std::fs::write(
+            &path,
+            "read line\necho id name stuck\necho oeiok\nexec sleep 60\n",
+        )
+        .unwrap();
+
+        let engine = External::spawn(&format!("sh {}", path.display())).unwrap();
+        assert_eq!(engine.name(), "stuck");
+
+        let start = Instant::now();
+        drop(engine);
+        assert!(start.elapsed() < QUIT_TIMEOUT * 5);
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,61 @@
+use super::*;
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::time::Duration;
+
+/// How many more plays a clock is shared between.
+const MOVES_TO_GO: u32 = 20;
+
+/// How much an engine may think for a play.
+///
+/// Limits left unset do not apply; with none set, engines fall back to their
+/// own default.
+#[derive(Copy, Clone, Default, Eq, PartialEq, Debug)]
+pub struct Limits {
+    /// How many plies deep to search (ignored by `Mcts`).
+    pub depth:    Option<usize>,
+    /// How many nodes to visit (playouts for `Mcts`).
+    pub nodes:    Option<u64>,
+    /// How long to think.
+    pub movetime: Option<Duration>,
+    /// The time left on the player's clock for the rest of the game.
+    pub clock:    Option<Duration>,
+}
+
+impl Limits {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// How long to think: `movetime`, or a share of `clock`, whichever is
+    /// shorter.
+    pub fn time(&self) -> Option<Duration> {
+        let share = self.clock.map(|clock| clock / MOVES_TO_GO);
+
+        match (self.movetime, share) {
+            (Some(movetime), Some(share)) => Some(movetime.min(share)),
+            (movetime, share) => movetime.or(share),
+        }
+    }
+}
+
+impl Display for Limits {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let mut limits = Vec::new();
+        if let Some(depth) = self.depth {
+            limits.push(format!("depth {}", depth));
+        }
+        if let Some(nodes) = self.nodes {
+            limits.push(format!("{} nodes", nodes));
+        }
+        if let Some(movetime) = self.movetime {
+            limits.push(format!("{} ms/play", movetime.as_millis()));
+        }
+        if let Some(clock) = self.clock {
+            limits.push(format!("{} s/game", clock.as_secs()));
+        }
+
+        write!(f, "{}", limits.join(", "))
+    }
+}
@@ -0,0 +1,333 @@
+use super::*;
+use std::time::Instant;
+
+/// How many playouts `Mcts` runs without limits.
+const DEFAULT_PLAYOUTS: u64 = 20_000;
+
+/// How `Mcts` plays out games.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Playout {
+    /// Uniformly random plays.
+    Random,
+    /// Random plays, but winning plays are always taken.
+    Light,
+}
+
+/// Playouts stop after that many plies, as a draw (when there is no draw
+/// timer).
+const MAX_PLIES: usize = 200;
+
+/// A Monte Carlo Tree Search (UCT) player, without evaluation.
+///
+/// The tree is kept between plays: the subtree of the position reached is
+/// reused when the next search starts.
+#[derive(Clone, Debug)]
+pub struct Mcts {
+    pub playout:     Playout,
+    /// The UCT exploration constant.
+    pub exploration: f64,
+    /// The playouts of the last search.
+    pub playouts:    u64,
+    rng:             Rng,
+    nodes:           Vec<Node>,
+    /// The key of the root position and its ply in the game.
+    root:            Option<(u64, usize)>,
+}
+
+#[derive(Clone, Debug)]
+struct Node {
+    /// The play from the parent, by `player`.
+    play:     Play,
+    player:   Player,
+    children: Vec<usize>,
+    /// The plays not expanded yet, `None` until the node is first visited.
+    untried:  Option<Vec<Play>>,
+    visits:   u32,
+    /// Wins of `player` (draws count half).
+    wins:     f64,
+}
+
+impl Mcts {
+    pub fn new(seed: Seed) -> Self {
+        Self {
+            playout:     Playout::Light,
+            exploration: 2f64.sqrt(),
+            playouts:    0,
+            rng:         Rng::new(seed),
+            nodes:       Vec::new(),
+            root:        None,
+        }
+    }
+
+    /// The most visited play in `game` after as many playouts as `limits`
+    /// allow (`Limits::nodes`), with its win rate.
+    pub fn search(&mut self, game: &Game, limits: &Limits) -> (Play, f64) {
+        let player = game.player().expect("game is over");
+        let start = Instant::now();
+        let time = limits.time();
+        let playouts = match limits.nodes {
+            None if time.is_none() => Some(DEFAULT_PLAYOUTS),
+            playouts => playouts,
+        };
+
+        self.reuse(game);
+        self.playouts = 0;
+
+        let mut game = game.clone();
+        while self.playouts == 0
+            || (playouts.is_none_or(|playouts| self.playouts < playouts)
+                && time.is_none_or(|time| start.elapsed() < time))
+        {
+            self.iterate(&mut game);
+            self.playouts += 1;
+        }
+
+        let best = self.nodes[0]
+            .children
+            .iter()
+            .map(|&child| &self.nodes[child])
+            .max_by_key(|child| child.visits)
+            .expect("no play");
+        debug_assert_eq!(best.player, player);
+
+        (best.play, best.wins / best.visits as f64)
+    }
+
+    /// Makes the node of `game` the root, keeping its subtree if it was
+    /// searched before.
+    fn reuse(&mut self, game: &Game) {
+        let ply = game.history().len();
+        let past = game.history().past();
+        let root = self.root.take().and_then(|(key, root_ply)| {
+            let key_at_root = if root_ply == ply {
+                game.key()
+            } else {
+                past.get(root_ply)?.key
+            };
+            if key_at_root != key || root_ply > ply {
+                return None;
+            }
+
+            let mut node = 0;
+            for record in &past[root_ply..] {
+                node = *self.nodes[node]
+                    .children
+                    .iter()
+                    .find(|&&child| self.nodes[child].play == record.play)?;
+            }
+            Some(node)
+        });
+
+        let nodes = std::mem::take(&mut self.nodes);
+        match root {
+            Some(root) => self.copy(&nodes, root),
+            None => self
+                .nodes
+                .push(Node::new(Play::default(), !game.player().unwrap())),
+        }
+        self.root = Some((game.key(), ply));
+    }
+
+    /// Copies the subtree of `nodes[root]` into `self.nodes`, root first.
+    fn copy(&mut self, nodes: &[Node], root: usize) {
+        // The nodes left to copy, with the index of their parent's copy
+        let mut stack = vec![(root, None::<usize>)];
+
+        while let Some((node, parent)) = stack.pop() {
+            let index = self.nodes.len();
+            self.nodes.push(Node {
+                children: Vec::new(),
+                ..nodes[node].clone()
+            });
+            if let Some(parent) = parent {
+                self.nodes[parent].children.push(index);
+            }
+
+            let children = nodes[node].children.iter().rev();
+            stack.extend(children.map(|&child| (child, Some(index))));
+        }
+    }
+
+    /// One selection, expansion, playout and backpropagation.
+    fn iterate(&mut self, game: &mut Game) {
+        let mut path = vec![0];
+        let mut node = 0;
+
+        // Selection and expansion
+        while let Some(player) = game.player() {
+            let untried = self.nodes[node].untried.get_or_insert_with(|| game.plays());
+
+            if !untried.is_empty() {
+                let i = self.rng.below(untried.len());
+                let play = untried.swap_remove(i);
+
+                game.play(play);
+                self.nodes.push(Node::new(play, player));
+                let child = self.nodes.len() - 1;
+                self.nodes[node].children.push(child);
+                path.push(child);
+                break;
+            }
+
+            node = self.select(node);
+            game.play(self.nodes[node].play);
+            path.push(node);
+        }
+
+        // Playout
+        let plies = path.len() - 1;
+        let mut playout = 0;
+        while let Some(player) = game.player() {
+            if playout == MAX_PLIES {
+                break;
+            }
+
+            let plays = game.legal_plays();
+            let play = match self.playout {
+                Playout::Light => plays
+                    .iter()
+                    .copied()
+                    .find(|&play| is_winning(game, player, play)),
+                Playout::Random => None,
+            }
+            .unwrap_or_else(|| plays[self.rng.below(plays.len())]);
+
+            game.play(play);
+            playout += 1;
+        }
+
+        // Backpropagation
+        let winner = match game.state() {
+            State::Won(winner) => Some(winner),
+            _ => None,
+        };
+        for &node in &path {
+            let node = &mut self.nodes[node];
+            node.visits += 1;
+            node.wins += match winner {
+                Some(winner) if winner == node.player => 1.0,
+                Some(_) => 0.0,
+                None => 0.5,
+            };
+        }
+
+        for _ in 0..plies + playout {
+            game.undo();
+        }
+    }
+
+    /// The child of `node` with the best UCT score.
+    fn select(&self, node: usize) -> usize {
+        let log = (self.nodes[node].visits as f64).ln();
+
+        *self.nodes[node]
+            .children
+            .iter()
+            .max_by(|&&a, &&b| {
+                let uct = |child: usize| {
+                    let child = &self.nodes[child];
+                    let visits = child.visits as f64;
+                    child.wins / visits + self.exploration * (log / visits).sqrt()
+                };
+                uct(a).total_cmp(&uct(b))
+            })
+            .expect("no children")
+    }
+}
+
+impl Node {
+    fn new(play: Play, player: Player) -> Self {
+        Self {
+            play,
+            player,
+            children: Vec::new(),
+            untried: None,
+            visits: 0,
+            wins: 0.0,
+        }
+    }
+}
+
+impl Engine for Mcts {
+    fn name(&self) -> String {
+        String::from("mcts")
+    }
+
+    fn play(&mut self, game: &Game, limits: &Limits) -> Result<Play, EngineError> {
+        let player = game.player().unwrap();
+
+        // Determinized games have no history to follow the tree along, and
+        // the tree was grown for other guesses of the ninjas anyway
+        if game.rules().shadow == Some(!player) {
+            self.root = None;
+        }
+
+        let game = game.determinize(player, &mut self.rng);
+        Ok(self.search(&game, limits).0)
+    }
+}
+
+/// Whether `play` wins on the spot, capturing the opponent's king or moving
+/// the king to the opponent's temple.
+fn is_winning(game: &Game, player: Player, play: Play) -> bool {
+    let rules = game.rules();
+    let (src, dest) = match play {
+        Play::Card { src, dest, .. } | Play::Wind { src, dest, .. } => (src, dest),
+        _ => return false,
+    };
+
+    (rules.stone && game[dest] == Some((!player, King)))
+        || (rules.stream
+            && game[src] == Some((player, King))
+            && dest == game.size().temple(!player))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Red's king on a1, Blue's on c3, Red holding Frog and Dog, which only
+    /// move it off the board, and Blue Tiger, which takes Blue's king to
+    /// Red's temple.
+    fn game(player: Player) -> Game {
+        let cards = Card::builtin();
+        let deal = Deal::parse("Frog,Dog,Tiger,Crab,Monkey", &cards).unwrap();
+
+        GameBuilder::empty(cards, deal, Rules::default())
+            .place(Square(A, One), Red, King)
+            .place(Square(C, Three), Blue, King)
+            .turn(player)
+            .build()
+            .unwrap()
+    }
+
+    fn limits() -> Limits {
+        Limits {
+            nodes: Some(1000),
+            ..Limits::default()
+        }
+    }
+
+    #[test]
+    fn mate_in_one() {
+        let mut game = game(Blue);
+        let (play, wins) = Mcts::new(Seed(1)).search(&game, &limits());
+
+        assert_eq!(game.play(play), State::Won(Blue));
+        assert!(wins > 0.9);
+    }
+
+    #[test]
+    fn reuse() {
+        let mut game = game(Red);
+        let mut mcts = Mcts::new(Seed(1));
+        let (discard, _) = mcts.search(&game, &limits());
+        game.play(discard);
+
+        let (play, _) = mcts.search(&game, &limits());
+        assert!(mcts.nodes[0].visits as u64 > mcts.playouts);
+        let (fresh, _) = Mcts::new(Seed(1)).search(&game, &limits());
+        assert_eq!(play, fresh);
+        assert_eq!(game.play(play), State::Won(Blue));
+    }
+}
@@ -0,0 +1,36 @@
+//! Computer players.
+
+mod alphabeta;
+mod eval;
+mod external;
+mod limits;
+mod mcts;
+mod protocol;
+mod table;
+mod tablebase;
+
+pub use alphabeta::*;
+pub use eval::*;
+pub use external::*;
+pub use limits::*;
+pub use mcts::*;
+pub use protocol::*;
+pub use table::*;
+pub use tablebase::*;
+
+use super::*;
+
+/// A computer player.
+///
+/// Engines are `Send`, so that they can think in the background.
+pub trait Engine: Send {
+    /// The name of this engine, for game records.
+    fn name(&self) -> String;
+
+    /// The play to make in `game`, which must not be over, thinking within
+    /// `limits`. Engines only look at what the player to move may see of
+    /// `game` (see `Game::determinize`).
+    ///
+    /// Only engine programs fail (see `External`), which forfeits the game.
+    fn play(&mut self, game: &Game, limits: &Limits) -> Result<Play, EngineError>;
+}
@@ -0,0 +1,354 @@
+//! The Onitama Engine Interface (OEI): a line-based text protocol between a
+//! program that runs games and an engine in another process, after UCI for
+//! chess.
+//!
+//! The program sends commands to the engine's standard input:
+//!
+//! - `oei` starts the session: the engine replies `id name <name>` then
+//!   `oeiok`,
+//! - `isready` waits for the engine: it replies `readyok`,
+//! - `newgame <cards> [<rule>...]` starts a game dealing the cards (Red's,
+//!   Blue's then the spare, separated by commas), with the default rules but
+//!   for these: `wind`, `shadow-red` or `shadow-blue`, `7x7`, `no-stone`,
+//!   `no-stream`, `discard-loss`, `timer <plies>` (0 for none) and `repetition
+//!   <n>` (see `Rules`),
+//! - `position <startpos|position> [moves <play>...]` sets the position, from
+//!   the start of the game or from a position (see `Position`), then plays,
+//! - `go [depth <plies>] [nodes <n>] [movetime <ms>] [clock <ms>]` searches
+//!   within these limits (see `Limits`): the engine replies `bestmove <play>`,
+//! - `quit` ends the session.
+//!
+//! Plays are written in move notation (see `Notation`), with `_` for spaces
+//! in card names and `:` between words: `Tiger:c1-c3`, `Ox:c1-c2:c3-c4`,
+//! `discard:Sea_Snake`. Card names use `_` for spaces in `newgame` too.
+//!
+//! Cards go by name only: with the cards of a card file, the engine must load
+//! the same file (`onitama engine --cards`).
+//!
+//! The engine replies `error <message>` to the commands it cannot follow.
+
+use super::*;
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// A command to an engine.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum Command {
+    Oei,
+    IsReady,
+    /// A game dealing these cards, by name, with `rules`.
+    NewGame {
+        cards: Vec<String>,
+        rules: Rules,
+    },
+    /// The position (`None` for the start of the game) then the plays, in
+    /// protocol form.
+    Position {
+        position: Option<String>,
+        moves:    Vec<String>,
+    },
+    Go(Limits),
+    Quit,
+}
+
+/// A reply of an engine.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum Reply {
+    Id(String),
+    OeiOk,
+    ReadyOk,
+    /// The play found, in protocol form.
+    BestMove(String),
+    Error(String),
+}
+
+/// `notation` in protocol form.
+pub fn to_protocol(notation: &Notation) -> String {
+    let card = notation.card().replace(' ', "_");
+
+    match notation {
+        Notation::Move {
+            src, dest, spirit, ..
+        } => match spirit {
+            Some((spirit_src, spirit_dest)) =>
+                format!("{}:{}-{}:{}-{}", card, src, dest, spirit_src, spirit_dest),
+            None => format!("{}:{}-{}", card, src, dest),
+        },
+        Notation::Discard(_) => format!("discard:{}", card),
+    }
+}
+
+/// The play of protocol form `str`.
+pub fn from_protocol(str: &str) -> Result<Notation, NotationError> {
+    str.replace([':', '_'], " ").parse()
+}
+
+impl Display for Command {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Oei => write!(f, "oei"),
+            Self::IsReady => write!(f, "isready"),
+            Self::NewGame { cards, rules } => {
+                let cards = cards.iter().map(|card| card.replace(' ', "_"));
+                write!(f, "newgame {}", cards.collect::<Vec<_>>().join(","))?;
+
+                if *rules != Rules::default() {
+                    write!(f, " {}", rules)?;
+                }
+                Ok(())
+            }
+            Self::Position { position, moves } => {
+                write!(f, "position {}", position.as_deref().unwrap_or("startpos"))?;
+                if !moves.is_empty() {
+                    write!(f, " moves {}", moves.join(" "))?;
+                }
+                Ok(())
+            }
+            Self::Go(limits) => {
+                write!(f, "go")?;
+                if let Some(depth) = limits.depth {
+                    write!(f, " depth {}", depth)?;
+                }
+                if let Some(nodes) = limits.nodes {
+                    write!(f, " nodes {}", nodes)?;
+                }
+                if let Some(movetime) = limits.movetime {
+                    write!(f, " movetime {}", movetime.as_millis())?;
+                }
+                if let Some(clock) = limits.clock {
+                    write!(f, " clock {}", clock.as_millis())?;
+                }
+                Ok(())
+            }
+            Self::Quit => write!(f, "quit"),
+        }
+    }
+}
+
+impl FromStr for Command {
+    type Err = ();
+
+    fn from_str(str: &str) -> Result<Self, ()> {
+        let mut words = str.split_whitespace();
+
+        match words.next() {
+            Some("oei") => Ok(Self::Oei),
+            Some("isready") => Ok(Self::IsReady),
+            Some("newgame") => {
+                let cards = words
+                    .next()
+                    .ok_or(())?
+                    .split(',')
+                    .map(|card| card.replace('_', " "))
+                    .collect();
+
+                let rules = words.collect::<Vec<_>>().join(" ").parse()?;
+
+                Ok(Self::NewGame { cards, rules })
+            }
+            Some("position") => {
+                let words = words.collect::<Vec<_>>();
+                let (position, moves) = match words.iter().position(|&word| word == "moves") {
+                    Some(i) => (&words[..i], &words[i + 1..]),
+                    None => (&words[..], &[][..]),
+                };
+
+                let position = match position {
+                    [] => return Err(()),
+                    ["startpos"] => None,
+                    position => Some(position.join(" ")),
+                };
+                let moves = moves.iter().map(|word| word.to_string()).collect();
+
+                Ok(Self::Position { position, moves })
+            }
+            Some("go") => {
+                let mut limits = Limits::default();
+
+                while let Some(word) = words.next() {
+                    let value = words.next().ok_or(())?;
+                    let number = value.parse::<u64>().map_err(|_| ())?;
+
+                    match word {
+                        "depth" => limits.depth = Some(number as usize),
+                        "nodes" => limits.nodes = Some(number),
+                        "movetime" => limits.movetime = Some(Duration::from_millis(number)),
+                        "clock" => limits.clock = Some(Duration::from_millis(number)),
+                        _ => return Err(()),
+                    }
+                }
+
+                Ok(Self::Go(limits))
+            }
+            Some("quit") => Ok(Self::Quit),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Display for Reply {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Id(name) => write!(f, "id name {}", name),
+            Self::OeiOk => write!(f, "oeiok"),
+            Self::ReadyOk => write!(f, "readyok"),
+            Self::BestMove(play) => write!(f, "bestmove {}", play),
+            Self::Error(message) => write!(f, "error {}", message),
+        }
+    }
+}
+
+impl FromStr for Reply {
+    type Err = ();
+
+    fn from_str(str: &str) -> Result<Self, ()> {
+        let str = str.trim();
+        let (word, rest) = str.split_once(' ').unwrap_or((str, ""));
+        let rest = rest.trim();
+
+        match word {
+            "id" => rest
+                .strip_prefix("name ")
+                .map(|name| Self::Id(name.trim().to_string()))
+                .ok_or(()),
+            "oeiok" => Ok(Self::OeiOk),
+            "readyok" => Ok(Self::ReadyOk),
+            "bestmove" if !rest.is_empty() => Ok(Self::BestMove(rest.to_string())),
+            "error" => Ok(Self::Error(rest.to_string())),
+            _ => Err(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commands() {
+        let commands = [
+            Command::Oei,
+            Command::IsReady,
+            Command::NewGame {
+                cards: ["Frog", "Sea Snake", "Horse", "Eel", "Rabbit"]
+                    .map(String::from)
+                    .to_vec(),
+                rules: Rules::default(),
+            },
+            Command::NewGame {
+                cards: ["Bat", "Eagle", "Wolf", "Falcon", "Plover"]
+                    .map(String::from)
+                    .to_vec(),
+                rules: Rules {
+                    stone:   false,
+                    stream:  false,
+                    draws:   Draws::both(12, 3),
+                    discard: Discard::Loss,
+                    wind:    true,
+                    shadow:  Some(Blue),
+                    size:    Size::SEVEN,
+                },
+            },
+            Command::NewGame {
+                cards: ["Tiger", "Crab", "Monkey", "Crane", "Dragon"]
+                    .map(String::from)
+                    .to_vec(),
+                rules: Rules {
+                    draws: Draws::NEVER,
+                    ..Rules::default()
+                },
+            },
+            Command::Position {
+                position: None,
+                moves:    Vec::new(),
+            },
+            Command::Position {
+                position: Some(String::from(
+                    "ppkpp/5/5/5/PPKPP r:Frog,Goose b:Horse,Eel s:Rabbit r 40",
+                )),
+                moves:    vec![String::from("Frog:b1-a2"), String::from("Horse:c5-c4")],
+            },
+            Command::Go(Limits::default()),
+            Command::Go(Limits {
+                depth:    Some(6),
+                nodes:    Some(100000),
+                movetime: Some(Duration::from_millis(1500)),
+                clock:    Some(Duration::from_secs(60)),
+            }),
+            Command::Quit,
+        ];
+
+        for command in commands {
+            assert_eq!(command.to_string().parse(), Ok(command));
+        }
+    }
+
+    #[test]
+    fn replies() {
+        let replies = [
+            Reply::Id(String::from("onitama alphabeta")),
+            Reply::OeiOk,
+            Reply::ReadyOk,
+            Reply::BestMove(String::from("Sea_Snake:c1-c2")),
+            Reply::Error(String::from("no position")),
+        ];
+
+        for reply in replies {
+            assert_eq!(reply.to_string().parse(), Ok(reply));
+        }
+    }
+
+    /// Every play of random games, and discards, survive the protocol form.
+    #[test]
+    fn plays() {
+        let mut rng = Rng::new(Seed(3));
+        let cards = Card::builtin();
+        let round_trip = |game: &Game, play: Play| {
+            let notation = to_protocol(&game.notation(play));
+            assert!(!notation.contains(' '));
+            assert_eq!(
+                from_protocol(&notation).and_then(|n| game.play_of(&n)),
+                Ok(play)
+            );
+        };
+
+        for (names, wind) in [
+            ("Sea Snake,Tiger,Crab,Monkey,Crane", false),
+            ("Sea Snake,Bat,Eagle,Mole,Octopus", true),
+        ] {
+            let deal = Deal::parse(names, &cards).unwrap();
+            let rules = Rules {
+                wind,
+                ..Rules::default()
+            };
+
+            for _ in 0..10 {
+                let mut game = Game::with_cards(cards.clone(), deal, rules);
+
+                while game.player().is_some() {
+                    let plays = game.legal_plays();
+                    for &play in &plays {
+                        round_trip(&game, play);
+                    }
+                    game.play(plays[rng.below(plays.len())]);
+                }
+            }
+        }
+
+        // Red's king cannot move with Frog or Dog from a1
+        let deal = Deal::parse("Frog,Dog,Tiger,Crab,Monkey", &cards).unwrap();
+        let game = GameBuilder::empty(cards, deal, Rules::default())
+            .place(Square(A, One), Red, King)
+            .place(Square(C, Three), Blue, King)
+            .turn(Red)
+            .build()
+            .unwrap();
+        for &play in &game.legal_plays() {
+            assert!(matches!(play, Play::Discard(_)));
+            round_trip(&game, play);
+        }
+    }
+}
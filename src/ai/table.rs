@@ -0,0 +1,77 @@
+use super::*;
+
+/// How an entry's score relates to the true score.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Bound {
+    Exact,
+    /// The true score is at least this (the search failed high).
+    Lower,
+    /// The true score is at most this (the search failed low).
+    Upper,
+}
+
+/// A searched position.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct Entry {
+    /// The canonical key of the position (see `Game::canonical_key`).
+    pub key:   u64,
+    pub depth: u8,
+    pub score: i32,
+    pub bound: Bound,
+    /// The best play, in the canonical position (see `Game::to_canonical`).
+    pub play:  Option<Play>,
+}
+
+/// A fixed-size transposition table, keyed by canonical key, so that
+/// mirrored positions share their entries.
+///
+/// Each key maps to a bucket of two entries: one kept while deeper than new
+/// entries, the other always replaced.
+#[derive(Clone, Debug)]
+pub struct Table {
+    buckets: Vec<[Option<Entry>; 2]>,
+}
+
+impl Table {
+    /// A table of about `bytes` bytes.
+    pub fn new(bytes: usize) -> Self {
+        let bucket = std::mem::size_of::<[Option<Entry>; 2]>();
+        let len = (bytes / bucket).max(1);
+
+        Self {
+            buckets: vec![[None; 2]; 1 << len.ilog2()],
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.buckets.fill([None; 2]);
+    }
+
+    /// The entry of the position of canonical key `key`.
+    pub fn probe(&self, key: u64) -> Option<Entry> {
+        self.bucket(key)
+            .iter()
+            .flatten()
+            .find(|entry| entry.key == key)
+            .copied()
+    }
+
+    pub fn store(&mut self, entry: Entry) {
+        let [deep, always] = self.bucket_mut(entry.key);
+
+        match deep {
+            Some(deep) if deep.key != entry.key && deep.depth > entry.depth =>
+                *always = Some(entry),
+            _ => *deep = Some(entry),
+        }
+    }
+
+    fn bucket(&self, key: u64) -> &[Option<Entry>; 2] {
+        &self.buckets[key as usize & (self.buckets.len() - 1)]
+    }
+
+    fn bucket_mut(&mut self, key: u64) -> &mut [Option<Entry>; 2] {
+        let len = self.buckets.len();
+        &mut self.buckets[key as usize & (len - 1)]
+    }
+}
@@ -0,0 +1,732 @@
+use super::*;
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::path::Path;
+
+/// The first bytes of tablebase files.
+const MAGIC: &[u8; 4] = b"OTB2";
+
+/// The most pawns per side `Tablebase::generate` accepts: two pawns per side
+/// would take 2.3 GB on a 5×5 board, and three times that to generate.
+/// Tablebases are for the kings' endgames, with at most a pawn to spare.
+pub const MAX_PAWNS: usize = 1;
+
+/// The byte of unresolved (then drawn) positions.
+const DRAW: u8 = 0;
+
+/// The byte of impossible positions: squares shared, or a king already in
+/// the opponent's temple.
+const INVALID: u8 = u8::MAX;
+
+/// The value of a position for the player to move, under perfect play.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Value {
+    /// Wins in that many plies.
+    Win(u8),
+    /// Loses in that many plies.
+    Loss(u8),
+    /// Neither player can force a win.
+    Draw,
+}
+
+impl Value {
+    fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            DRAW => Some(Self::Draw),
+            INVALID => None,
+            plies if plies % 2 == 1 => Some(Self::Win(plies)),
+            plies => Some(Self::Loss(plies)),
+        }
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match *self {
+            Self::Win(plies) => write!(f, "win in {}", plies),
+            Self::Loss(plies) => write!(f, "loss in {}", plies),
+            Self::Draw => write!(f, "draw"),
+        }
+    }
+}
+
+/// The value of every position of a deal with both kings and at most
+/// `pawns` pawns per side, computed by retrograde analysis.
+///
+/// Positions cover piece placement, the distribution of the five cards
+/// between the hands and the spare, and the player to move. Values assume
+/// the base rules (Way of the Stone and of the Stream, discarding allowed)
+/// without draw rules: a draw is a position neither player can win. Wins and
+/// losses that the draw timer would cut short are not probed.
+///
+/// Files store one byte per position: 0 for a draw, odd plies for a win, even
+/// plies for a loss.
+#[derive(Clone, Debug)]
+pub struct Tablebase {
+    /// The dealt cards, by name.
+    cards:        [Card; Deal::LEN],
+    size:         Size,
+    pawns:        usize,
+    /// Each distribution of the cards: Red's, Blue's (in `cards` order), then
+    /// the spare.
+    arrangements: Vec<[usize; Deal::LEN]>,
+    /// The arrangement once a player swapped a card of their hand with the
+    /// spare, by arrangement, player and hand slot.
+    swaps:        Vec<[[usize; HAND]; 2]>,
+    /// The placements of both sides' pawns around the kings.
+    placements:   usize,
+    values:       Vec<u8>,
+}
+
+/// A position of the tablebase.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+struct Setup {
+    player:      Player,
+    arrangement: usize,
+    kings:       [Square; 2],
+    pawns:       [Bitboard; 2],
+}
+
+impl Tablebase {
+    /// Solves the deal of `deal`'s cards on a board of `size`, with at most
+    /// `pawns` (up to `MAX_PAWNS`) pawns per side.
+    pub fn generate(cards: &[Card], deal: Deal, size: Size, pawns: usize) -> Self {
+        assert!(pawns <= MAX_PAWNS, "too many pawns");
+
+        let mut dealt = deal.cards().map(|card| cards[card].clone());
+        dealt.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut tablebase = Self::new(dealt, size, pawns);
+        let len = tablebase.len();
+        let mut counts = vec![0u16; len];
+        let mut frontier = Vec::new();
+
+        // Immediate wins, and how many plays the other positions have
+        for (index, count) in counts.iter_mut().enumerate() {
+            let Some(setup) = tablebase.decode(index) else {
+                continue;
+            };
+
+            let mut win = false;
+            let mut plays = 0;
+            tablebase.successors(&setup, |next| match next {
+                Some(_) => plays += 1,
+                None => win = true,
+            });
+
+            if win {
+                tablebase.values[index] = 1;
+                frontier.push(index);
+            } else {
+                tablebase.values[index] = DRAW;
+                *count = plays;
+            }
+        }
+
+        // Positions whose plays all reach wins of the opponent are lost,
+        // positions with a play reaching a loss of the opponent are won
+        let mut values = std::mem::take(&mut tablebase.values);
+        let mut plies = 1;
+        while !frontier.is_empty() {
+            assert!(plies + 1 < INVALID, "too long to mate");
+
+            let mut next = Vec::new();
+            for &index in &frontier {
+                let setup = tablebase.decode(index).unwrap();
+
+                tablebase.predecessors(&setup, |prev| {
+                    if values[prev] != DRAW {
+                        return;
+                    }
+                    if plies % 2 == 1 {
+                        counts[prev] -= 1;
+                        if counts[prev] > 0 {
+                            return;
+                        }
+                    }
+                    values[prev] = plies + 1;
+                    next.push(prev);
+                });
+            }
+
+            frontier = next;
+            plies += 1;
+        }
+
+        tablebase.values = values;
+        tablebase
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), TablebaseError> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(self.size.get() as u8);
+        bytes.push(self.pawns as u8);
+        for card in &self.cards {
+            bytes.push(card.name.len() as u8);
+            bytes.extend(card.name.bytes());
+        }
+        bytes.extend(&self.values);
+
+        std::fs::write(path, bytes).map_err(|error| TablebaseError::Io(error.to_string()))
+    }
+
+    /// Loads the tablebase of `path`, whose cards are taken from `cards`.
+    pub fn load(path: impl AsRef<Path>, cards: &[Card]) -> Result<Self, TablebaseError> {
+        let bytes = std::fs::read(path).map_err(|error| TablebaseError::Io(error.to_string()))?;
+        let mut bytes = bytes.as_slice();
+        let mut take = |len: usize| {
+            let taken = bytes.get(..len).ok_or(TablebaseError::Format)?;
+            bytes = &bytes[len..];
+            Ok(taken)
+        };
+
+        if take(MAGIC.len())? != MAGIC {
+            return Err(TablebaseError::Format);
+        }
+        let size = Size::try_from(take(1)?[0] as usize).map_err(|_| TablebaseError::Format)?;
+        let pawns = take(1)?[0] as usize;
+        if pawns > MAX_PAWNS {
+            return Err(TablebaseError::Format);
+        }
+
+        let mut dealt = Vec::with_capacity(Deal::LEN);
+        for _ in 0..Deal::LEN {
+            let len = take(1)?[0] as usize;
+            let name = String::from_utf8_lossy(take(len)?);
+
+            dealt.push(
+                cards
+                    .iter()
+                    .find(|card| card.name == name)
+                    .ok_or_else(|| TablebaseError::Card(name.to_string()))?
+                    .clone(),
+            );
+        }
+        let dealt = dealt.try_into().unwrap();
+
+        let mut tablebase = Self::new(dealt, size, pawns);
+        let len = tablebase.len();
+        tablebase.values = take(len)?.to_vec();
+        if !bytes.is_empty() {
+            return Err(TablebaseError::Format);
+        }
+
+        Ok(tablebase)
+    }
+
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// The most pawns per side of the positions covered.
+    pub fn pawns(&self) -> usize {
+        self.pawns
+    }
+
+    /// The dealt cards, by name.
+    pub fn cards(&self) -> &[Card; Deal::LEN] {
+        &self.cards
+    }
+
+    /// The values of all the possible positions.
+    pub fn values(&self) -> impl '_ + Iterator<Item = Value> {
+        self.values
+            .iter()
+            .filter_map(|&byte| Value::from_byte(byte))
+    }
+
+    /// The value of `game`, if covered: its deal, its rules (draw rules
+    /// aside) and board size, not over, few enough pawns, and a win or loss
+    /// ending before `Game::timer` runs out.
+    pub fn probe(&self, game: &Game) -> Option<Value> {
+        let setup = self.setup(game)?;
+
+        match Value::from_byte(self.values[self.index(&setup)])? {
+            // The timer might draw first, unless a capture resets it
+            Value::Win(plies) | Value::Loss(plies) if plies > game.timer() => None,
+            value => Some(value),
+        }
+    }
+
+    /// The best play of `game` with its value: the fastest win, else a draw,
+    /// else the slowest loss.
+    pub fn best(&self, game: &Game) -> Option<(Play, Value)> {
+        let value = self.probe(game)?;
+        let player = game.player()?;
+        let mut game = game.clone();
+        let mut best = None;
+
+        for &play in &game.legal_plays() {
+            game.play(play);
+            let score = match game.state() {
+                State::Won(winner) if winner == player => i32::MAX,
+                State::Won(_) => i32::MIN,
+                State::Draw(_) => 0,
+                State::Turn(_) => match self.probe(&game) {
+                    Some(Value::Loss(plies)) => i32::MAX - 1 - plies as i32,
+                    Some(Value::Win(plies)) => i32::MIN + 1 + plies as i32,
+                    _ => 0,
+                },
+            };
+            game.undo();
+
+            if best.is_none_or(|(_, best)| score > best) {
+                best = Some((play, score));
+            }
+        }
+
+        best.map(|(play, _)| (play, value))
+    }
+}
+
+impl Tablebase {
+    fn new(cards: [Card; Deal::LEN], size: Size, pawns: usize) -> Self {
+        let mut arrangements = Vec::new();
+        for spare in 0..Deal::LEN {
+            let rest = (0..Deal::LEN)
+                .filter(|&card| card != spare)
+                .collect::<Vec<_>>();
+
+            for red0 in 0..rest.len() {
+                for red1 in red0 + 1..rest.len() {
+                    let blue = rest
+                        .iter()
+                        .enumerate()
+                        .filter(|&(i, _)| i != red0 && i != red1)
+                        .map(|(_, &card)| card)
+                        .collect::<Vec<_>>();
+
+                    arrangements.push([rest[red0], rest[red1], blue[0], blue[1], spare]);
+                }
+            }
+        }
+
+        let swaps = arrangements
+            .iter()
+            .map(|arrangement| {
+                [Red, Blue].map(|player| {
+                    [0, 1].map(|slot| {
+                        let mut swapped = *arrangement;
+                        swapped.swap(2 * player.index() + slot, 2 * HAND);
+                        let hand = &mut swapped[2 * player.index()..2 * player.index() + HAND];
+                        hand.sort_unstable();
+
+                        arrangements
+                            .iter()
+                            .position(|&arrangement| arrangement == swapped)
+                            .unwrap()
+                    })
+                })
+            })
+            .collect();
+
+        let mut tablebase = Self {
+            cards,
+            size,
+            pawns,
+            arrangements,
+            swaps,
+            placements: 0,
+            values: Vec::new(),
+        };
+        let free = size.squares_len() - 2;
+        tablebase.placements = (0..=pawns)
+            .map(|red| choose(free, red) * tablebase.sets(free - red))
+            .sum();
+        tablebase.values = vec![INVALID; tablebase.len()];
+        tablebase
+    }
+
+    fn len(&self) -> usize {
+        let squares = self.size.squares_len();
+        2 * self.arrangements.len() * squares * (squares - 1) * self.placements
+    }
+
+    /// The number of placements of at most `pawns` pawns on `free` squares.
+    fn sets(&self, free: usize) -> usize {
+        (0..=self.pawns).map(|len| choose(free, len)).sum()
+    }
+
+    /// The rank of `pawns` among the placements of as many pawns on the
+    /// squares not `taken`, in the combinatorial number system.
+    fn rank(&self, pawns: Bitboard, taken: Bitboard) -> usize {
+        pawns
+            .squares()
+            .enumerate()
+            .map(|(i, square)| {
+                let below = Bitboard((1 << square.index()) - 1);
+                choose(self.size.index(square) - (taken & below).len(), i + 1)
+            })
+            .sum()
+    }
+
+    /// The placement of `len` pawns of `rank` (see `rank`).
+    fn unrank(&self, len: usize, mut rank: usize, taken: Bitboard) -> Bitboard {
+        let mut free = (0..self.size.squares_len())
+            .map(|index| self.size.square(index))
+            .filter(|&square| !taken.contains(square));
+        let mut pawns = Bitboard::EMPTY;
+
+        for i in (1..=len).rev() {
+            let mut last = i - 1;
+            while choose(last + 1, i) <= rank {
+                last += 1;
+            }
+            rank -= choose(last, i);
+            pawns |= free.clone().nth(last).unwrap().into();
+        }
+
+        pawns
+    }
+
+    /// Positions are indexed by player, arrangement, kings (Blue's among the
+    /// squares Red's king leaves), then Red's pawns and Blue's among the
+    /// squares left, grouped by number of pawns, so that no index has
+    /// pieces sharing a square.
+    fn index(&self, setup: &Setup) -> usize {
+        let squares = self.size.squares_len();
+        let [red, blue] = setup.kings.map(|king| self.size.index(king));
+        let kings = Bitboard::from(setup.kings[0]) | Bitboard::from(setup.kings[1]);
+        let free = squares - 2;
+        let reds = setup.pawns[0].len();
+        let blues = self.sets(free - reds);
+
+        let mut pawns = (0..reds)
+            .map(|len| choose(free, len) * self.sets(free - len))
+            .sum::<usize>();
+        pawns += self.rank(setup.pawns[0], kings) * blues;
+        pawns += (0..setup.pawns[1].len())
+            .map(|len| choose(free - reds, len))
+            .sum::<usize>();
+        pawns += self.rank(setup.pawns[1], kings | setup.pawns[0]);
+
+        let mut index = setup.player.index();
+        index = index * self.arrangements.len() + setup.arrangement;
+        index = index * squares + red;
+        index = index * (squares - 1) + blue - usize::from(blue > red);
+        index * self.placements + pawns
+    }
+
+    /// The position of `index`, if possible.
+    fn decode(&self, mut index: usize) -> Option<Setup> {
+        let mut next = |len: usize| {
+            let digit = index % len;
+            index /= len;
+            digit
+        };
+
+        let squares = self.size.squares_len();
+        let mut pawns = next(self.placements);
+        let blue_king = next(squares - 1);
+        let red_king = next(squares);
+        let blue_king = blue_king + usize::from(blue_king >= red_king);
+        let arrangement = next(self.arrangements.len());
+        let player = if next(2) == 0 { Red } else { Blue };
+
+        let [red_king, blue_king] = [red_king, blue_king].map(|king| self.size.square(king));
+        let kings = Bitboard::from(red_king) | Bitboard::from(blue_king);
+        let free = squares - 2;
+        let mut reds = 0;
+        while pawns >= choose(free, reds) * self.sets(free - reds) {
+            pawns -= choose(free, reds) * self.sets(free - reds);
+            reds += 1;
+        }
+        let blues = self.sets(free - reds);
+        let red_pawns = self.unrank(reds, pawns / blues, kings);
+        let mut pawns = pawns % blues;
+        let mut blues = 0;
+        while pawns >= choose(free - reds, blues) {
+            pawns -= choose(free - reds, blues);
+            blues += 1;
+        }
+        let blue_pawns = self.unrank(blues, pawns, kings | red_pawns);
+
+        if red_king == self.size.temple(Blue) || blue_king == self.size.temple(Red) {
+            return None;
+        }
+
+        Some(Setup {
+            player,
+            arrangement,
+            kings: [red_king, blue_king],
+            pawns: [red_pawns, blue_pawns],
+        })
+    }
+
+    /// The position of `game`, if covered.
+    fn setup(&self, game: &Game) -> Option<Setup> {
+        let rules = game.rules();
+        if !rules.stone
+            || !rules.stream
+            || rules.discard != Discard::Allowed
+            || rules.wind
+            || rules.shadow.is_some()
+            || rules.size != self.size
+        {
+            return None;
+        }
+
+        let player = game.player()?;
+        let slot = |card: &Card| self.cards.iter().position(|c| c.name == card.name);
+        let mut dealt = [0; Deal::LEN];
+        for (i, card) in game
+            .cards(Red)
+            .into_iter()
+            .chain(game.cards(Blue))
+            .chain([game.spare()])
+            .enumerate()
+        {
+            dealt[i] = slot(card)?;
+        }
+        dealt[..HAND].sort_unstable();
+        dealt[HAND..2 * HAND].sort_unstable();
+
+        let arrangement = self.arrangements.iter().position(|&a| a == dealt)?;
+        let kings = [Red, Blue].map(|player| game[(player, King)]);
+        let kings = [kings[0]?, kings[1]?];
+        let pawns = [Red, Blue]
+            .map(|player| game[player].occupied & !Bitboard::from(kings[player.index()]));
+        if pawns.iter().any(|pawns| pawns.len() > self.pawns) {
+            return None;
+        }
+
+        Some(Setup {
+            player,
+            arrangement,
+            kings,
+            pawns,
+        })
+    }
+
+    fn hand(&self, setup: &Setup, player: Player) -> [&Card; HAND] {
+        let arrangement = &self.arrangements[setup.arrangement];
+        [0, 1].map(|slot| &self.cards[arrangement[HAND * player.index() + slot]])
+    }
+
+    /// Calls `f` with the index of the position after each play of `setup`,
+    /// or `None` for the plays that win on the spot.
+    fn successors(&self, setup: &Setup, mut f: impl FnMut(Option<usize>)) {
+        let player = setup.player;
+        let (p, o) = (player.index(), (!player).index());
+        let own = setup.pawns[p] | Bitboard::from(setup.kings[p]);
+        let mut moved = false;
+
+        for (slot, card) in self.hand(setup, player).into_iter().enumerate() {
+            for src in own.squares() {
+                for dest in (card.dests(player, src, self.size) & !own).squares() {
+                    moved = true;
+
+                    let king = src == setup.kings[p];
+                    if dest == setup.kings[o] || (king && dest == self.size.temple(!player)) {
+                        f(None);
+                        continue;
+                    }
+
+                    let mut next = *setup;
+                    next.player = !player;
+                    next.arrangement = self.swaps[setup.arrangement][p][slot];
+                    if king {
+                        next.kings[p] = dest;
+                    } else {
+                        next.pawns[p] ^= Bitboard::from(src) | Bitboard::from(dest);
+                    }
+                    next.pawns[o] &= !Bitboard::from(dest);
+
+                    f(Some(self.index(&next)));
+                }
+            }
+        }
+
+        if !moved {
+            for slot in 0..HAND {
+                let next = Setup {
+                    player: !player,
+                    arrangement: self.swaps[setup.arrangement][p][slot],
+                    ..*setup
+                };
+
+                f(Some(self.index(&next)));
+            }
+        }
+    }
+
+    /// Calls `f` with the index of each position with a play reaching
+    /// `setup`.
+    fn predecessors(&self, setup: &Setup, mut f: impl FnMut(usize)) {
+        let player = !setup.player;
+        let (p, o) = (player.index(), setup.player.index());
+        let card = &self.cards[self.arrangements[setup.arrangement][2 * HAND]];
+        let occupied = setup.pawns[0]
+            | setup.pawns[1]
+            | Bitboard::from(setup.kings[0])
+            | Bitboard::from(setup.kings[1]);
+
+        for slot in 0..HAND {
+            let prev = Setup {
+                player,
+                arrangement: self.swaps[setup.arrangement][p][slot],
+                ..*setup
+            };
+
+            for dest in (setup.pawns[p] | Bitboard::from(setup.kings[p])).squares() {
+                // The squares the piece came from, moving backwards
+                for src in (card.dests(!player, dest, self.size) & !occupied).squares() {
+                    let mut prev = prev;
+                    if dest == setup.kings[p] {
+                        prev.kings[p] = src;
+                    } else {
+                        prev.pawns[p] ^= Bitboard::from(src) | Bitboard::from(dest);
+                    }
+
+                    f(self.index(&prev));
+
+                    if prev.pawns[o].len() < self.pawns {
+                        prev.pawns[o] |= Bitboard::from(dest);
+                        f(self.index(&prev));
+                    }
+                }
+            }
+
+            // Discards, when no piece could move
+            if !self.has_moves(&prev) {
+                f(self.index(&prev));
+            }
+        }
+    }
+
+    /// Whether the player to move can move a piece (or must discard).
+    fn has_moves(&self, setup: &Setup) -> bool {
+        let p = setup.player.index();
+        let own = setup.pawns[p] | Bitboard::from(setup.kings[p]);
+
+        self.hand(setup, setup.player).into_iter().any(|card| {
+            own.squares()
+                .any(|src| !(card.dests(setup.player, src, self.size) & !own).is_empty())
+        })
+    }
+}
+
+/// The number of ways to choose `k` of `n` things.
+fn choose(n: usize, k: usize) -> usize {
+    if k > n {
+        return 0;
+    }
+
+    (0..k).fold(1, |product, i| product * (n - i) / (i + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Red's king on a1, Blue's on c3, Red holding Frog and Dog, which only
+    /// move it off the board, and Blue Tiger, which takes Blue's king to
+    /// Red's temple.
+    fn solve(player: Player, timer: u8) -> (Tablebase, Game) {
+        let cards = Card::builtin();
+        let deal = Deal::parse("Frog,Dog,Tiger,Crab,Monkey", &cards).unwrap();
+        let tablebase = Tablebase::generate(&cards, deal, Size::FIVE, 0);
+        let game = GameBuilder::empty(cards, deal, Rules::default())
+            .place(Square(A, One), Red, King)
+            .place(Square(C, Three), Blue, King)
+            .turn(player)
+            .timer(timer)
+            .build()
+            .unwrap();
+
+        (tablebase, game)
+    }
+
+    #[test]
+    fn mate_in_one() {
+        let (tablebase, game) = solve(Blue, 40);
+
+        assert_eq!(tablebase.probe(&game), Some(Value::Win(1)));
+        let (play, _) = tablebase.best(&game).unwrap();
+        assert_eq!(game.notation(play).to_string(), "Tiger c3-c1");
+    }
+
+    #[test]
+    fn discard_loss() {
+        let (tablebase, game) = solve(Red, 40);
+
+        assert!(game
+            .legal_plays()
+            .iter()
+            .all(|play| matches!(play, Play::Discard(_))));
+        assert_eq!(tablebase.probe(&game), Some(Value::Loss(2)));
+
+        // The timer draws before Blue gets to move
+        let (tablebase, game) = solve(Red, 1);
+        assert_eq!(tablebase.probe(&game), None);
+    }
+
+    #[test]
+    fn index() {
+        let cards = Card::builtin();
+        let deal = Deal::parse("Frog,Dog,Tiger,Crab,Monkey", &cards).unwrap();
+        let tablebase = Tablebase::new(deal.cards().map(|card| cards[card].clone()), Size::FIVE, 1);
+
+        assert_eq!(tablebase.len(), 2 * 30 * 25 * 24 * (24 + 23 * 23));
+        for index in (0..tablebase.len()).step_by(9973) {
+            if let Some(setup) = tablebase.decode(index) {
+                assert_eq!(tablebase.index(&setup), index);
+            }
+        }
+    }
+
+    /// Takes minutes unoptimized: `cargo test --release -- --ignored`.
+    #[test]
+    #[ignore]
+    fn alpha_beta() {
+        let cards = Card::builtin();
+        let deal = Deal::parse("Frog,Dog,Tiger,Crab,Monkey", &cards).unwrap();
+        let tablebase = Tablebase::generate(&cards, deal, Size::FIVE, 1);
+        let rules = Rules {
+            draws: Draws::NEVER,
+            ..Rules::default()
+        };
+        let mut rng = Rng::new(Seed(1));
+        let mut checked = 0;
+
+        while checked < 50 {
+            let mut squares = Size::FIVE.squares().collect::<Vec<_>>();
+            let mut dealt = deal.cards();
+            rng.shuffle(&mut dealt);
+            let dealt = Deal {
+                red:   [dealt[0], dealt[1]],
+                blue:  [dealt[2], dealt[3]],
+                spare: dealt[4],
+            };
+            let player = if rng.below(2) == 0 { Red } else { Blue };
+            let mut take = || squares.swap_remove(rng.below(squares.len()));
+            let Ok(game) = GameBuilder::empty(cards.clone(), dealt, rules)
+                .place(take(), Red, King)
+                .place(take(), Blue, King)
+                .place(take(), Red, PawnA)
+                .place(take(), Blue, PawnA)
+                .turn(player)
+                .build()
+            else {
+                continue;
+            };
+            let plies = match tablebase.probe(&game) {
+                Some(Value::Win(plies) | Value::Loss(plies)) if plies <= 5 => plies,
+                _ => continue,
+            };
+
+            let limits = Limits {
+                depth: Some(plies as usize),
+                ..Limits::default()
+            };
+            let (_, score) = AlphaBeta::new().search(&game, &limits);
+            let expected = match tablebase.probe(&game) {
+                Some(Value::Win(_)) => WIN - plies as i32,
+                _ => -(WIN - plies as i32),
+            };
+            assert_eq!(score, expected, "{}", game.position());
+            checked += 1;
+        }
+    }
+}
@@ -0,0 +1,292 @@
+use super::*;
+use ai::Engine;
+use std::path::PathBuf;
+use std::process::exit;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The command line options limiting engines (see `parse_limits`).
+pub const LIMITS_USAGE: &str =
+    "    --movetime <ms>             How long engines think per play (default: 1000, unless
+                                another limit is given)
+    --clock <s>                 How long engines think in the whole game
+    --depth <plies>             How deep alphabeta searches
+    --nodes <n>                 How many nodes engines visit per play (playouts for mcts)";
+
+/// The command line options choosing a game, shared by the subcommands.
+pub struct GameArgs {
+    pub seed:     Seed,
+    pub sets:     Vec<CardSet>,
+    pub cards:    Option<PathBuf>,
+    pub deal:     Option<String>,
+    pub position: Option<String>,
+    pub wind:     bool,
+    pub shadow:   Option<Player>,
+    pub size:     Size,
+    pub draws:    Draws,
+    pub stone:    bool,
+    pub stream:   bool,
+    pub discard:  Discard,
+}
+
+impl GameArgs {
+    pub const USAGE: &'static str = "    --seed <hex>                Deal from this seed
+    --sets <sets>               Deal from these card sets: base, senseis-path,
+                                way-of-the-wind (implies --wind), long-range, e.g.
+                                \"base,senseis-path\"
+    --cards <file>              Deal from the cards of this card file
+    --deal <cards>              Deal these cards (Red's, Blue's, spare), e.g.
+                                \"Frog,Goose,Horse,Eel,Rabbit\"
+    --position <position>       Start from this position, e.g.
+                                \"ppkpp/5/5/5/PPKPP r:Frog,Goose b:Horse,Eel s:Rabbit r 40\"
+    --wind                      Play with the Wind Spirit (Way of the Wind)
+    --shadow <red|blue>         Make that player's students ninjas (Light and Shadow)
+    --size <5|7>                Play on a 5×5 (default) or 7×7 board
+    --draws <rules>             Draw after so many plies without a capture, or when
+                                a position repeats so many times: timer:<plies>,
+                                repetition:<n>, both separated by a comma, or none
+                                (default: timer:40)
+    --no-stone                  Do not win by capturing the king (Way of the Stone)
+    --no-stream                 Do not win by reaching the temple (Way of the Stream)
+    --discard-loss              Lose instead of discarding when no piece can move";
+
+    /// Parses `arg` (taking its value from `args`), or returns `false` if it
+    /// is not a game option.
+    pub fn parse(
+        &mut self,
+        arg: &str,
+        args: &mut impl Iterator<Item = String>,
+        usage: &str,
+    ) -> bool {
+        match arg {
+            "--seed" => self.seed = value(args.next(), usage),
+            "--sets" =>
+                self.sets = value::<String>(args.next(), usage)
+                    .split(',')
+                    .map(|set| set.parse().unwrap_or_else(|_| error(usage)))
+                    .collect(),
+            "--cards" => self.cards = Some(value(args.next(), usage)),
+            "--deal" => self.deal = Some(value(args.next(), usage)),
+            "--position" => self.position = Some(value(args.next(), usage)),
+            "--wind" => self.wind = true,
+            "--shadow" => self.shadow = Some(value(args.next(), usage)),
+            "--size" =>
+                self.size = Size::try_from(value::<usize>(args.next(), usage))
+                    .unwrap_or_else(|_| error(usage)),
+            "--draws" =>
+                self.draws = parse_draws(&value::<String>(args.next(), usage))
+                    .unwrap_or_else(|| error(usage)),
+            "--no-stone" => self.stone = false,
+            "--no-stream" => self.stream = false,
+            "--discard-loss" => self.discard = Discard::Loss,
+            _ => return false,
+        }
+        true
+    }
+
+    pub fn rules(&self) -> Rules {
+        Rules {
+            wind:    self.wind || self.sets.contains(&CardSet::WayOfTheWind),
+            shadow:  self.shadow,
+            size:    self.size,
+            draws:   self.draws,
+            stone:   self.stone,
+            stream:  self.stream,
+            discard: self.discard,
+        }
+    }
+
+    /// `CARDS`, or the cards of `--cards`.
+    pub fn cards(&self) -> Cards {
+        match &self.cards {
+            None => Card::builtin(),
+            Some(path) => {
+                let cards = Card::load(path, self.rules().size)
+                    .unwrap_or_else(|err| error(&format!("{}: {}", path.display(), err)));
+                if cards.len() < Deal::LEN {
+                    error(&format!(
+                        "{}: at least {} cards needed",
+                        path.display(),
+                        Deal::LEN
+                    ));
+                }
+                cards
+            }
+        }
+    }
+
+    /// The cards to deal from, among `cards` (from `cards()`): those of
+    /// `--sets`, or all the cards of `--cards`.
+    pub fn dealt(&self, cards: &[Card]) -> Vec<usize> {
+        match &self.cards {
+            None => CardSet::union(&self.sets),
+            Some(_) => (0..cards.len()).collect(),
+        }
+    }
+
+    /// The game of `--position` (on its own board size, and not over), or of
+    /// `--deal`, or dealt from `--seed`.
+    pub fn game(&self) -> Game {
+        let rules = self.rules();
+        let cards = self.cards();
+
+        if let Some(position) = &self.position {
+            let game = Position::parse(position, &cards)
+                .and_then(|position| {
+                    let size = position.size;
+                    Game::from_position(position, Rules { size, ..rules })
+                })
+                .unwrap_or_else(|err| error(&format!("--position: {}", err)));
+            if game.player().is_none() {
+                error("--position: the game is over");
+            }
+            return game;
+        }
+
+        let deal = match &self.deal {
+            Some(deal) => Deal::parse(deal, &cards).unwrap_or_else(|| {
+                error(&format!(
+                    "--deal: expected {} distinct card names",
+                    Deal::LEN
+                ))
+            }),
+            None => Deal::random_from(self.seed, &self.dealt(&cards))
+                .unwrap_or_else(|| error(&format!("at least {} cards needed", Deal::LEN))),
+        };
+
+        Game::with_cards(cards, deal, rules)
+    }
+}
+
+impl Default for GameArgs {
+    fn default() -> Self {
+        Self {
+            seed:     Seed::random(),
+            sets:     vec![CardSet::Base],
+            cards:    None,
+            deal:     None,
+            position: None,
+            wind:     false,
+            shadow:   None,
+            size:     Size::default(),
+            draws:    Draws::default(),
+            stone:    true,
+            stream:   true,
+            discard:  Discard::Allowed,
+        }
+    }
+}
+
+/// Parses `--draws`: `timer:<plies>`, `repetition:<n>`, both separated by a
+/// comma, or `none`.
+fn parse_draws(str: &str) -> Option<Draws> {
+    let mut draws = Draws::NEVER;
+    if str == "none" {
+        return Some(draws);
+    }
+
+    for rule in str.split(',') {
+        let (name, n) = rule.split_once(':')?;
+        let n = n.parse().ok().filter(|&n| n > 0)?;
+
+        match name {
+            "timer" => draws.timer = Some(n),
+            "repetition" => draws.repetition = Some(n),
+            _ => return None,
+        }
+    }
+
+    Some(draws)
+}
+
+/// A computer player chosen on the command line.
+#[derive(Clone, Debug)]
+pub enum EngineArgs {
+    AlphaBeta,
+    Mcts {
+        seed: Seed,
+    },
+    /// An engine program, with its arguments.
+    External(String),
+}
+
+impl EngineArgs {
+    /// Parses `alphabeta`, `mcts` (seeded with `seed`) or `engine:<command>`.
+    pub fn parse(name: &str, seed: Seed) -> Option<Self> {
+        match name {
+            "alphabeta" => Some(Self::AlphaBeta),
+            "mcts" => Some(Self::Mcts { seed }),
+            name => name
+                .strip_prefix("engine:")
+                .map(|command| Self::External(command.to_string())),
+        }
+    }
+
+    /// This engine, seeded with `seed` if it takes a seed.
+    pub fn with_seed(&self, seed: Seed) -> Self {
+        match self {
+            Self::Mcts { .. } => Self::Mcts { seed },
+            engine => engine.clone(),
+        }
+    }
+
+    /// The engine, probing `tablebase` if it can. Exits if an engine program
+    /// cannot be started.
+    pub fn build(&self, tablebase: Option<&Arc<ai::Tablebase>>) -> Box<dyn Engine> {
+        match self {
+            Self::AlphaBeta => {
+                let mut engine = ai::AlphaBeta::new();
+                engine.tablebase = tablebase.cloned();
+                Box::new(engine)
+            }
+            Self::Mcts { seed } => Box::new(ai::Mcts::new(*seed)),
+            Self::External(command) => Box::new(
+                ai::External::spawn(command)
+                    .unwrap_or_else(|err| error(&format!("{}: {}", command, err))),
+            ),
+        }
+    }
+}
+
+/// Parses `arg` into `limits` (taking its value from `args`), or returns
+/// `false` if it is not a limit option.
+pub fn parse_limits(
+    limits: &mut ai::Limits,
+    arg: &str,
+    args: &mut impl Iterator<Item = String>,
+    usage: &str,
+) -> bool {
+    match arg {
+        "--movetime" => limits.movetime = Some(Duration::from_millis(value(args.next(), usage))),
+        "--clock" => limits.clock = Some(Duration::from_secs(value(args.next(), usage))),
+        "--depth" => limits.depth = Some(value(args.next(), usage)),
+        "--nodes" => limits.nodes = Some(value(args.next(), usage)),
+        _ => return false,
+    }
+    true
+}
+
+/// `limits`, or a second per play if empty.
+pub fn or_default(limits: ai::Limits) -> ai::Limits {
+    if limits.is_empty() {
+        ai::Limits {
+            movetime: Some(Duration::from_secs(1)),
+            ..limits
+        }
+    } else {
+        limits
+    }
+}
+
+/// Parses `arg`, or exits with `usage`.
+pub fn value<T: FromStr>(arg: Option<String>, usage: &str) -> T {
+    arg.and_then(|arg| arg.parse().ok())
+        .unwrap_or_else(|| error(usage))
+}
+
+/// Prints `message` and exits.
+pub fn error(message: &str) -> ! {
+    eprintln!("{}", message);
+    exit(1);
+}
@@ -0,0 +1,93 @@
+use super::*;
+use std::ops::BitAnd;
+use std::ops::BitAndAssign;
+use std::ops::BitOr;
+use std::ops::BitOrAssign;
+use std::ops::BitXor;
+use std::ops::BitXorAssign;
+use std::ops::Not;
+
+/// A set of squares, one bit per square (see `Square::index`).
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Default, Debug)]
+pub struct Bitboard(pub u64);
+
+impl Bitboard {
+    pub const EMPTY: Self = Self(0);
+    pub const FULL: Self = Self((1 << SQUARES) - 1);
+
+    pub const fn square(index: usize) -> Self {
+        Self(1 << index)
+    }
+
+    pub fn contains(&self, square: Square) -> bool {
+        self.0 & (1 << square.index()) != 0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn squares(self) -> impl Iterator<Item = Square> {
+        let mut bits = self.0;
+
+        std::iter::from_fn(move || {
+            if bits == 0 {
+                None
+            } else {
+                let index = bits.trailing_zeros() as usize;
+                bits &= bits - 1;
+                Some(Square::from(index))
+            }
+        })
+    }
+}
+
+impl From<Square> for Bitboard {
+    fn from(square: Square) -> Self {
+        Self::square(square.index())
+    }
+}
+
+impl FromIterator<Square> for Bitboard {
+    fn from_iter<I: IntoIterator<Item = Square>>(squares: I) -> Self {
+        squares
+            .into_iter()
+            .fold(Self::EMPTY, |bitboard, square| bitboard | square.into())
+    }
+}
+
+impl Not for Bitboard {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        Self(!self.0 & Self::FULL.0)
+    }
+}
+
+macro_rules! ops {
+    ($($Op:ident $op:ident $OpAssign:ident $op_assign:ident)*) => { $(
+        impl $Op for Bitboard {
+            type Output = Self;
+
+            fn $op(self, rhs: Self) -> Self {
+                Self(self.0.$op(rhs.0))
+            }
+        }
+
+        impl $OpAssign for Bitboard {
+            fn $op_assign(&mut self, rhs: Self) {
+                self.0.$op_assign(rhs.0);
+            }
+        }
+    )* };
+}
+
+ops!(
+    BitAnd bitand BitAndAssign bitand_assign
+    BitOr  bitor  BitOrAssign  bitor_assign
+    BitXor bitxor BitXorAssign bitxor_assign
+);
@@ -3,23 +3,22 @@ use std::ops::Index;
 use std::ops::IndexMut;
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
-pub struct Board(pub [[Option<(Player, Piece)>; SIZE]; SIZE]);
-
-impl Default for Board {
-    fn default() -> Self {
-        macro_rules! file {
-            ($red:expr, $blue:expr) => {
-                [Some((Red, $red)), None, None, None, Some((Blue, $blue))]
-            };
+pub struct Board(pub [[Option<(Player, Piece)>; MAX_SIZE]; MAX_SIZE]);
+
+impl Board {
+    pub const EMPTY: Self = Self([[None; MAX_SIZE]; MAX_SIZE]);
+
+    /// The starting position on a board of `size`.
+    pub fn new(size: Size) -> Self {
+        let mut board = Self::EMPTY;
+
+        for piece in size.pieces() {
+            for player in [Red, Blue] {
+                board[Square(piece.file(size), size.home(player))] = Some((player, piece));
+            }
         }
 
-        Self([
-            file!(PawnA, PawnA),
-            file!(PawnB, PawnB),
-            file!(King, King),
-            file!(PawnD, PawnD),
-            file!(PawnE, PawnE),
-        ])
+        board
     }
 }
 
@@ -0,0 +1,163 @@
+use super::*;
+
+/// Builds a `Game` from an arbitrary position: endgame studies, tests,
+/// handicap games...
+///
+/// ```
+/// let cards = Card::builtin();
+/// let deal = Deal::parse("Frog,Goose,Horse,Eel,Rabbit", &cards).unwrap();
+/// let game = GameBuilder::empty(cards, deal, Rules::default())
+///     .place(Square(C, One), Red, King)
+///     .place(Square(C, Five), Blue, King)
+///     .place(Square(A, Four), Red, PawnA)
+///     .turn(Blue)
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct GameBuilder {
+    position: Position,
+    rules:    Rules,
+}
+
+impl GameBuilder {
+    /// The starting position of `deal`.
+    pub fn new(cards: Cards, deal: Deal, rules: Rules) -> Self {
+        Self::from_position(Game::with_cards(cards, deal, rules).position(), rules)
+    }
+
+    /// The starting position of `deal`, without pieces.
+    pub fn empty(cards: Cards, deal: Deal, rules: Rules) -> Self {
+        let mut builder = Self::new(cards, deal, rules);
+        builder.position.board = Board::EMPTY;
+        builder.position.hidden = Bitboard::EMPTY;
+        builder
+    }
+
+    pub fn from_position(position: Position, rules: Rules) -> Self {
+        Self { position, rules }
+    }
+
+    /// Puts `player`'s `piece` on `square`, replacing what was there.
+    pub fn place(mut self, square: Square, player: Player, piece: Piece) -> Self {
+        self = self.remove(square);
+        self.position.board[square] = Some((player, piece));
+        self
+    }
+
+    /// Empties `square`.
+    pub fn remove(mut self, square: Square) -> Self {
+        self.position.board[square] = None;
+        self.position.hidden &= !Bitboard::from(square);
+        self
+    }
+
+    /// Hides the ninja on `square` (Light and Shadow).
+    pub fn hide(mut self, square: Square) -> Self {
+        self.position.hidden |= Bitboard::from(square);
+        self
+    }
+
+    pub fn hand(mut self, player: Player, cards: [usize; HAND]) -> Self {
+        match player {
+            Red => self.position.deal.red = cards,
+            Blue => self.position.deal.blue = cards,
+        }
+        self
+    }
+
+    pub fn spare(mut self, card: usize) -> Self {
+        self.position.deal.spare = card;
+        self
+    }
+
+    /// Gives the turn to `player`.
+    pub fn turn(self, player: Player) -> Self {
+        self.state(State::Turn(player))
+    }
+
+    pub fn state(mut self, state: State) -> Self {
+        self.position.state = state;
+        self
+    }
+
+    pub fn timer(mut self, timer: u8) -> Self {
+        self.position.timer = timer;
+        self
+    }
+
+    /// Moves the Wind Spirit (Way of the Wind).
+    pub fn spirit(mut self, spirit: Option<Square>) -> Self {
+        self.position.spirit = spirit;
+        self
+    }
+
+    pub fn position(&self) -> Position {
+        self.position.clone()
+    }
+
+    /// The game, if the position is consistent (see `Game::from_position`).
+    pub fn build(&self) -> Result<Game, PositionError> {
+        Game::from_position(self.position.clone(), self.rules)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn builder() -> GameBuilder {
+        let cards = Card::builtin();
+        let deal = Deal::parse("Frog,Goose,Horse,Eel,Rabbit", &cards).unwrap();
+
+        GameBuilder::empty(cards, deal, Rules::default())
+            .place(Square(C, One), Red, King)
+            .place(Square(C, Five), Blue, King)
+    }
+
+    #[test]
+    fn build() {
+        let game = builder()
+            .place(Square(A, Four), Red, PawnA)
+            .turn(Blue)
+            .build()
+            .unwrap();
+
+        assert_eq!(game.player(), Some(Blue));
+        assert_eq!(
+            game.position().to_string(),
+            "2k2/P4/5/5/2K2 r:Frog,Goose b:Horse,Eel s:Rabbit b 40"
+        );
+    }
+
+    #[test]
+    fn duplicate_kings() {
+        assert_eq!(
+            builder().place(Square(A, One), Red, King).build(),
+            Err(PositionError::Duplicate(Red, King))
+        );
+    }
+
+    #[test]
+    fn too_many_pawns() {
+        assert_eq!(
+            builder().place(Square(A, Four), Blue, PawnF).build(),
+            Err(PositionError::Pieces(Blue))
+        );
+    }
+
+    /// A king may stand on its own temple, but not on the opponent's.
+    #[test]
+    fn temples() {
+        assert!(builder().build().is_ok());
+        assert_eq!(
+            builder()
+                .remove(Square(C, Five))
+                .place(Square(B, Five), Blue, King)
+                .place(Square(C, Five), Red, King)
+                .remove(Square(C, One))
+                .build(),
+            Err(PositionError::State)
+        );
+    }
+}
@@ -1,8 +1,165 @@
 use super::*;
+use std::borrow::Cow;
+use std::ops::Range;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::OnceLock;
 
-#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+/// The cards games are dealt from, shared: `CARDS` (see `Card::builtin`) or
+/// cards loaded with `Card::load`.
+pub type Cards = Arc<[Card]>;
+
+#[derive(Clone, Eq, PartialEq, Debug)]
 pub struct Card {
-    pub name:  &'static str,
-    pub stamp: Player,
-    pub moves: Moves,
+    pub name:     Cow<'static, str>,
+    pub stamp:    Player,
+    pub moves:    Moves,
+    /// The Wind Spirit's moves, for wind cards (Way of the Wind).
+    pub spirit:   Moves,
+    masks:        [[Bitboard; SQUARES]; 2],
+    spirit_masks: [[Bitboard; SQUARES]; 2],
+}
+
+impl Card {
+    pub const fn new(name: &'static str, stamp: Player, moves: &'static [Move]) -> Self {
+        Self::wind(name, stamp, moves, &[])
+    }
+
+    pub const fn wind(
+        name: &'static str,
+        stamp: Player,
+        moves: &'static [Move],
+        spirit: &'static [Move],
+    ) -> Self {
+        Self {
+            name: Cow::Borrowed(name),
+            stamp,
+            moves: Cow::Borrowed(moves),
+            spirit: Cow::Borrowed(spirit),
+            masks: masks(moves),
+            spirit_masks: masks(spirit),
+        }
+    }
+
+    /// A card made at run time (see `Card::load`), a wind card if `spirit`
+    /// has moves.
+    pub fn owned(name: String, stamp: Player, moves: Vec<Move>, spirit: Vec<Move>) -> Self {
+        Self {
+            masks: masks(&moves),
+            spirit_masks: masks(&spirit),
+            name: Cow::Owned(name),
+            stamp,
+            moves: Cow::Owned(moves),
+            spirit: Cow::Owned(spirit),
+        }
+    }
+
+    /// `CARDS`, shared by all the games dealt from them.
+    pub fn builtin() -> Cards {
+        static BUILTIN: OnceLock<Cards> = OnceLock::new();
+        BUILTIN.get_or_init(|| CARDS.into()).clone()
+    }
+
+    /// Whether this card moves a piece then the Wind Spirit.
+    pub fn is_wind(&self) -> bool {
+        !self.spirit.is_empty()
+    }
+
+    /// The squares `player` can reach from `square` with this card, on a
+    /// board of `size`.
+    pub fn dests(&self, player: Player, square: Square, size: Size) -> Bitboard {
+        self.masks[player.index()][square.index()] & size.area()
+    }
+
+    /// The squares `player` can move the Wind Spirit to from `square` with
+    /// this wind card, on a board of `size`.
+    pub fn spirit_dests(&self, player: Player, square: Square, size: Size) -> Bitboard {
+        self.spirit_masks[player.index()][square.index()] & size.area()
+    }
+}
+
+/// A set of cards, from the base game or an expansion.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum CardSet {
+    Base,
+    SenseisPath,
+    /// Wind cards, which move a student then the Wind Spirit (with
+    /// `Rules::wind`).
+    WayOfTheWind,
+    /// Cards reaching three squares away, for the 7×7 board (see
+    /// `Rules::size`). Not a published set.
+    LongRange,
+}
+
+impl CardSet {
+    pub const ALL: [Self; 4] = [
+        Self::Base,
+        Self::SenseisPath,
+        Self::WayOfTheWind,
+        Self::LongRange,
+    ];
+
+    /// The indices in `CARDS` of the cards of this set.
+    pub fn cards(&self) -> Range<usize> {
+        match *self {
+            Self::Base => 0..16,
+            Self::SenseisPath => 16..32,
+            Self::WayOfTheWind => 32..40,
+            Self::LongRange => 40..48,
+        }
+    }
+
+    /// The indices in `CARDS` of the cards of `sets`.
+    pub fn union(sets: &[Self]) -> Vec<usize> {
+        sets.iter().flat_map(|set| set.cards()).collect()
+    }
+}
+
+impl FromStr for CardSet {
+    type Err = ();
+
+    fn from_str(str: &str) -> Result<Self, ()> {
+        match str {
+            "base" => Ok(Self::Base),
+            "senseis-path" => Ok(Self::SenseisPath),
+            "way-of-the-wind" => Ok(Self::WayOfTheWind),
+            "long-range" => Ok(Self::LongRange),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Precomputes the destinations of `moves` for each player and square, on a
+/// `MAX_SIZE` board.
+const fn masks(moves: &[Move]) -> [[Bitboard; SQUARES]; 2] {
+    const fn bit(file: isize, rank: isize) -> u64 {
+        let size = MAX_SIZE as isize;
+
+        if 0 <= file && file < size && 0 <= rank && rank < size {
+            1 << (rank * size + file)
+        } else {
+            0
+        }
+    }
+
+    let mut masks = [[Bitboard::EMPTY; SQUARES]; 2];
+    let mut index = 0;
+
+    while index < SQUARES {
+        let file = (index % MAX_SIZE) as isize;
+        let rank = (index / MAX_SIZE) as isize;
+        let mut i = 0;
+
+        while i < moves.len() {
+            let (x, y) = moves[i].offset();
+
+            masks[Red as usize][index].0 |= bit(file + x, rank + y);
+            masks[Blue as usize][index].0 |= bit(file - x, rank - y);
+            i += 1;
+        }
+
+        index += 1;
+    }
+
+    masks
 }
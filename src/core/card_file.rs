@@ -0,0 +1,256 @@
+//! Custom cards, from a text file.
+//!
+//! Each card starts with a `<name> <red|blue>` line, followed by either:
+//! - the lines of a 5×5 (or 7×7) grid, Red's forward on top, where `o` is the
+//!   piece, `x` a move, `s` a Wind Spirit move and `.` nothing,
+//! - or, on the same line after a `:`, a list of `file,rank` offsets (e.g.
+//!   `-1,1`), Wind Spirit moves coming after a `|`.
+//!
+//! Moves must stay within the board of the rules played: ±2 on 5×5, ±3 on
+//! 7×7.
+//!
+//! Blank lines and lines starting with `#` are ignored:
+//!
+//! ```text
+//! # Tiger, as a grid
+//! Tiger blue
+//! . . x . .
+//! . . . . .
+//! . . o . .
+//! . . x . .
+//! . . . . .
+//!
+//! # Crab, as offsets
+//! Crab blue: 0,1 -2,0 2,0
+//! ```
+
+use super::*;
+use std::fs;
+use std::path::Path;
+
+impl Card {
+    /// Loads the cards of the file at `path`, for a board of `size`.
+    pub fn load(path: impl AsRef<Path>, size: Size) -> Result<Cards, CardError> {
+        let str = fs::read_to_string(path).map_err(|error| CardError::Io(error.to_string()))?;
+        Self::parse_all(&str, size)
+    }
+
+    /// Parses the cards of a card file, for a board of `size`.
+    pub fn parse_all(str: &str, size: Size) -> Result<Cards, CardError> {
+        let mut lines = str
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+        let mut cards = Vec::<Card>::new();
+
+        while let Some((line, header)) = lines.next() {
+            let error = |kind| CardError::Invalid { line, kind };
+            let (header, offsets) = match header.split_once(':') {
+                Some((header, offsets)) => (header, Some(offsets)),
+                None => (header, None),
+            };
+            let (name, stamp) = header
+                .trim()
+                .rsplit_once(' ')
+                .ok_or(error(CardErrorKind::Header))?;
+            let name = name.trim();
+            let stamp = stamp.parse().map_err(|_| error(CardErrorKind::Stamp))?;
+
+            if cards.iter().any(|card| card.name == name) {
+                return Err(error(CardErrorKind::Duplicate));
+            }
+
+            let (moves, spirit) = match offsets {
+                Some(offsets) => parse_offsets(offsets, size).map_err(error)?,
+                None => {
+                    // As many rows as the first one has squares
+                    let mut rows = Vec::<Vec<char>>::new();
+                    while rows.first().is_none_or(|first| rows.len() < first.len()) {
+                        let (line, row) = lines.next().ok_or(error(CardErrorKind::Grid))?;
+                        let row = parse_row(row, rows.first().map(Vec::len))
+                            .map_err(|kind| CardError::Invalid { line, kind })?;
+                        rows.push(row);
+                    }
+                    parse_grid(&rows).map_err(error)?
+                }
+            };
+
+            check(&moves, size).map_err(error)?;
+            if !spirit.is_empty() {
+                check(&spirit, size).map_err(error)?;
+            }
+
+            cards.push(Card::owned(name.to_owned(), stamp, moves, spirit));
+        }
+
+        Ok(cards.into())
+    }
+}
+
+fn parse_offsets(str: &str, size: Size) -> Result<(Vec<Move>, Vec<Move>), CardErrorKind> {
+    let parse = |str: &str| {
+        str.split_whitespace()
+            .map(|offset| {
+                let (file, rank) = offset.split_once(',').ok_or(CardErrorKind::Offset)?;
+                let file = file.parse().map_err(|_| CardErrorKind::Offset)?;
+                let rank = rank.parse().map_err(|_| CardErrorKind::Offset)?;
+                Move::from_offset(file, rank).ok_or(CardErrorKind::Range(file, rank, size))
+            })
+            .collect::<Result<Vec<_>, _>>()
+    };
+
+    match str.split_once('|') {
+        Some((moves, spirit)) => Ok((parse(moves)?, parse(spirit)?)),
+        None => Ok((parse(str)?, vec![])),
+    }
+}
+
+/// The squares of a row, `len` of them if given, else 5 or 7.
+fn parse_row(str: &str, len: Option<usize>) -> Result<Vec<char>, CardErrorKind> {
+    let row = str
+        .chars()
+        .filter(|char| !char.is_whitespace())
+        .collect::<Vec<_>>();
+
+    match len {
+        Some(len) if row.len() != len => Err(CardErrorKind::Grid),
+        None if Size::try_from(row.len()).is_err() => Err(CardErrorKind::Grid),
+        _ => Ok(row),
+    }
+}
+
+fn parse_grid(rows: &[Vec<char>]) -> Result<(Vec<Move>, Vec<Move>), CardErrorKind> {
+    let center = (rows.len() / 2) as isize;
+    let mut moves = vec![];
+    let mut spirit = vec![];
+
+    for (y, row) in rows.iter().enumerate() {
+        for (x, char) in row.iter().enumerate() {
+            let (file, rank) = (x as isize - center, center - y as isize);
+            let center = (file, rank) == (0, 0);
+
+            match char.to_ascii_lowercase() {
+                'o' if center => {}
+                '.' if !center => {}
+                'x' | 's' if center => return Err(CardErrorKind::Origin),
+                'x' => moves.push(Move::from_offset(file, rank).unwrap()),
+                's' => spirit.push(Move::from_offset(file, rank).unwrap()),
+                _ => return Err(CardErrorKind::Grid),
+            }
+        }
+    }
+
+    Ok((moves, spirit))
+}
+
+fn check(moves: &[Move], size: Size) -> Result<(), CardErrorKind> {
+    let reach = (size.get() / 2) as isize;
+    let far = moves
+        .iter()
+        .map(Move::offset)
+        .find(|&(file, rank)| file.abs() > reach || rank.abs() > reach);
+
+    if moves.is_empty() {
+        Err(CardErrorKind::NoMoves)
+    } else if let Some((file, rank)) = far {
+        Err(CardErrorKind::Range(file, rank, size))
+    } else if moves.iter().any(|mov| mov.offset() == (0, 0)) {
+        Err(CardErrorKind::Origin)
+    } else if (1..moves.len()).any(|i| moves[..i].contains(&moves[i])) {
+        Err(CardErrorKind::Duplicate)
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn offsets(moves: &[Move]) -> Vec<(isize, isize)> {
+        let mut offsets = moves.iter().map(Move::offset).collect::<Vec<_>>();
+        offsets.sort_unstable();
+        offsets
+    }
+
+    fn error(str: &str, size: Size) -> CardError {
+        Card::parse_all(str, size).unwrap_err()
+    }
+
+    fn invalid(line: usize, kind: CardErrorKind) -> CardError {
+        CardError::Invalid { line, kind }
+    }
+
+    #[test]
+    fn valid() {
+        let str = "\
+# Tiger, as a grid
+Tiger blue
+. . x . .
+. . . . .
+. . o . .
+. . x . .
+. . . . .
+
+# Crab, as offsets
+Crab blue: 0,1 -2,0 2,0
+Breeze red: 1,0 | 0,1 0,-1
+";
+        let cards = Card::parse_all(str, Size::FIVE).unwrap();
+        let builtin = Card::builtin();
+
+        assert_eq!(cards.len(), 3);
+        for card in &cards[..2] {
+            let builtin = builtin.iter().find(|c| c.name == card.name).unwrap();
+            assert_eq!(card.stamp, builtin.stamp);
+            assert_eq!(offsets(&card.moves), offsets(&builtin.moves));
+            assert!(!card.is_wind());
+        }
+        assert_eq!(cards[2].stamp, Red);
+        assert_eq!(offsets(&cards[2].moves), [(1, 0)]);
+        assert_eq!(offsets(&cards[2].spirit), [(0, -1), (0, 1)]);
+    }
+
+    #[test]
+    fn invalid_cards() {
+        assert_eq!(
+            error("Crab blue: 0,1\n\nCrab red: 1,0", Size::FIVE),
+            invalid(3, CardErrorKind::Duplicate)
+        );
+        assert_eq!(
+            error("Crab blue: 0,1 0,1", Size::FIVE),
+            invalid(1, CardErrorKind::Duplicate)
+        );
+        assert_eq!(
+            error("Leap blue: 0,3", Size::FIVE),
+            invalid(1, CardErrorKind::Range(0, 3, Size::FIVE))
+        );
+        assert!(Card::parse_all("Leap blue: 0,3", Size::SEVEN).is_ok());
+        assert_eq!(
+            error("Leap blue: 0,4", Size::SEVEN),
+            invalid(1, CardErrorKind::Range(0, 4, Size::SEVEN))
+        );
+        assert_eq!(error("Crab", Size::FIVE), invalid(1, CardErrorKind::Header));
+        assert_eq!(
+            error("Crab green: 0,1", Size::FIVE),
+            invalid(1, CardErrorKind::Stamp)
+        );
+        assert_eq!(
+            error("Crab blue: 0;1", Size::FIVE),
+            invalid(1, CardErrorKind::Offset)
+        );
+        assert_eq!(
+            error("Crab blue: 0,0", Size::FIVE),
+            invalid(1, CardErrorKind::Origin)
+        );
+        assert_eq!(
+            error("Tiger blue\n. . x . .\n. . o . .", Size::FIVE),
+            invalid(1, CardErrorKind::Grid)
+        );
+        assert_eq!(
+            error("Tiger blue\n. x .\n. o .\n. . .", Size::FIVE),
+            invalid(2, CardErrorKind::Grid)
+        );
+    }
+}
@@ -0,0 +1,78 @@
+use super::*;
+
+/// The cards of a game, as indices in `CARDS` (or in the cards given to
+/// `Game::with_cards`).
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct Deal {
+    pub red:   [usize; HAND],
+    pub blue:  [usize; HAND],
+    pub spare: usize,
+}
+
+impl Deal {
+    pub const LEN: usize = 2 * HAND + 1;
+
+    /// Deals `Deal::LEN` distinct cards from the base game.
+    pub fn random(seed: Seed) -> Self {
+        Self::random_from(seed, &CardSet::Base.cards().collect::<Vec<_>>()).unwrap()
+    }
+
+    /// Deals `Deal::LEN` distinct cards from `sets`, if they have enough.
+    pub fn random_from_sets(seed: Seed, sets: &[CardSet]) -> Option<Self> {
+        Self::random_from(seed, &CardSet::union(sets))
+    }
+
+    /// Deals `Deal::LEN` distinct cards from `cards` (indices in a card set),
+    /// if there are enough.
+    pub fn random_from(seed: Seed, cards: &[usize]) -> Option<Self> {
+        let mut cards = cards.to_vec();
+        cards.sort_unstable();
+        cards.dedup();
+        if cards.len() < Self::LEN {
+            return None;
+        }
+
+        let mut rng = Rng::new(seed);
+        rng.shuffle(&mut cards);
+
+        Some(Self {
+            red:   [cards[0], cards[1]],
+            blue:  [cards[2], cards[3]],
+            spare: cards[4],
+        })
+    }
+
+    /// Parses `Deal::LEN` distinct card names of `cards`, separated by commas:
+    /// Red's, Blue's, then the spare.
+    pub fn parse(str: &str, cards: &[Card]) -> Option<Self> {
+        let cards = str
+            .split(',')
+            .map(|name| {
+                cards
+                    .iter()
+                    .position(|card| card.name.eq_ignore_ascii_case(name.trim()))
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        match cards[..] {
+            [red0, red1, blue0, blue1, spare]
+                if !(1..cards.len()).any(|i| cards[..i].contains(&cards[i])) =>
+                Some(Self {
+                    red: [red0, red1],
+                    blue: [blue0, blue1],
+                    spare,
+                }),
+            _ => None,
+        }
+    }
+
+    pub fn cards(&self) -> [usize; Self::LEN] {
+        [
+            self.red[0],
+            self.red[1],
+            self.blue[0],
+            self.blue[1],
+            self.spare,
+        ]
+    }
+}
@@ -0,0 +1,302 @@
+use super::*;
+use std::error::Error;
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::io;
+
+/// Why a `Play` was rejected by `Game::try_play`.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum PlayError {
+    /// The game is already over.
+    Over(State),
+    /// There is no piece on the source square.
+    Empty(Square),
+    /// The piece on the source square belongs to the opponent.
+    Opponent(Square),
+    /// The card index is not in `0..HAND`.
+    Card(usize),
+    /// The destination cannot be reached from the source with that card.
+    Unreachable(Square),
+    /// Discarding is only allowed when no card can be played.
+    Discard,
+    /// A wind card moves a piece then the Wind Spirit.
+    Wind,
+    /// The Wind Spirit cannot move to that square.
+    Spirit(Square),
+}
+
+impl Display for PlayError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match *self {
+            Self::Over(State::Won(player)) => write!(f, "game is over ({:?} won)", player),
+            Self::Over(_) => write!(f, "game is over"),
+            Self::Empty(square) => write!(f, "no piece on {}", square),
+            Self::Opponent(square) => write!(f, "piece on {} belongs to the opponent", square),
+            Self::Card(card) => write!(f, "no card at index {} (hand has {})", card, HAND),
+            Self::Unreachable(square) => write!(f, "{} is not reachable with that card", square),
+            Self::Discard => write!(f, "cannot discard while a card can be played"),
+            Self::Wind => write!(f, "a wind card moves a piece then the Wind Spirit"),
+            Self::Spirit(square) => write!(f, "the Wind Spirit cannot move to {}", square),
+        }
+    }
+}
+
+impl Error for PlayError {}
+
+/// Why a `Notation` could not be parsed or converted to a `Play`.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum NotationError {
+    /// The text is not `<card> <square>-<square> [<square>-<square>]` or
+    /// `discard <card>`.
+    Syntax,
+    /// The game is over, there is no player to move.
+    Over,
+    /// The player to move has no card of that name.
+    Card(String),
+    /// The Wind Spirit is not on that square.
+    Spirit(Square),
+}
+
+impl Display for NotationError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Syntax => write!(
+                f,
+                "expected `<card> <from>-<to> [<from>-<to>]` or `discard <card>`"
+            ),
+            Self::Over => write!(f, "game is over"),
+            Self::Card(card) => write!(f, "no {} card in hand", card),
+            Self::Spirit(square) => write!(f, "the Wind Spirit is not on {}", square),
+        }
+    }
+}
+
+impl Error for NotationError {}
+
+/// Why a position string or `Position` was rejected by `Position::parse` or
+/// `Game::from_position`.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum PositionError {
+    /// The fields are not `<board> r:<cards> b:<cards> s:<card> <state>
+    /// <timer>`.
+    Syntax,
+    /// The board is not five or seven ranks of as many squares of pieces,
+    /// Wind Spirit and empty runs, or has pieces off the board.
+    Board,
+    /// The board is not of `Rules::size`.
+    Size,
+    /// No card has that name.
+    Card(String),
+    /// The dealt cards are not `Deal::LEN` distinct cards.
+    Cards,
+    /// That player has no king (and has not lost).
+    Kings(Player),
+    /// That piece appears more than once.
+    Duplicate(Player, Piece),
+    /// That player has more pawns than the board has (or pawns from a larger
+    /// board).
+    Pieces(Player),
+    /// The game should be over.
+    State,
+    /// The Wind Spirit is missing, unexpected or on a piece.
+    Spirit,
+    /// Hidden ninjas are not pawns of `Rules::shadow`.
+    Hidden,
+    /// The timer is above `Draws::limit`.
+    Timer,
+}
+
+impl Display for PositionError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Syntax => write!(
+                f,
+                "expected `<board> r:<card>,<card> b:<card>,<card> s:<card> <state> <timer>`"
+            ),
+            Self::Board => write!(f, "expected 5 or 7 ranks of `KPNkpnw` or digits"),
+            Self::Size => write!(f, "the board size does not match the rules"),
+            Self::Card(card) => write!(f, "unknown card {}", card),
+            Self::Cards => write!(f, "expected {} distinct cards", Deal::LEN),
+            Self::Kings(player) => write!(f, "{:?} must have a king", player),
+            Self::Duplicate(player, piece) =>
+                write!(f, "{:?} {:?} appears more than once", player, piece),
+            Self::Pieces(player) => write!(f, "{:?} has too many pawns", player),
+            Self::State => write!(f, "a king is on the opponent's temple"),
+            Self::Spirit => write!(f, "the Wind Spirit must be on an empty square with --wind"),
+            Self::Hidden => write!(f, "only the shadow player's students can be hidden"),
+            Self::Timer => write!(f, "timer is above the draw limit"),
+        }
+    }
+}
+
+impl Error for PositionError {}
+
+/// Why a game record was rejected by `GameRecord::load`.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum RecordError {
+    /// The file could not be read or written.
+    Io(String),
+    /// The record is invalid on that line.
+    Invalid { line: usize, kind: RecordErrorKind },
+}
+
+/// What is wrong with a line of a game record.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum RecordErrorKind {
+    /// The tag is not `[Name "value"]` or comes after the plays.
+    Tag,
+    /// The deal is missing or is not five known card names.
+    Deal,
+    /// The variant is not made of `wind`, `shadow-red` and `shadow-blue`.
+    Variant,
+    /// The starting position is invalid.
+    Position(PositionError),
+    /// The play cannot be read.
+    Notation(NotationError),
+    /// The play is illegal.
+    Play(PlayError),
+    /// The result does not match the game.
+    Result,
+}
+
+impl Display for RecordError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "{}", error),
+            Self::Invalid { line, kind } => write!(f, "line {}: {}", line, kind),
+        }
+    }
+}
+
+impl Display for RecordErrorKind {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Tag => write!(f, "expected `[Name \"value\"]`"),
+            Self::Deal => write!(f, "expected a deal of {} card names", Deal::LEN),
+            Self::Variant => write!(f, "expected `wind`, `shadow-red` or `shadow-blue`"),
+            Self::Position(error) => write!(f, "{}", error),
+            Self::Notation(error) => write!(f, "{}", error),
+            Self::Play(error) => write!(f, "{}", error),
+            Self::Result => write!(f, "result does not match the game"),
+        }
+    }
+}
+
+impl Error for RecordError {}
+
+/// Why a card file was rejected by `Card::load`.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum CardError {
+    /// The file could not be read.
+    Io(String),
+    /// The card on that line is invalid.
+    Invalid { line: usize, kind: CardErrorKind },
+}
+
+/// What is wrong with a card in a card file.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum CardErrorKind {
+    /// The header is not `<name> <red|blue>`.
+    Header,
+    /// The stamp is not `red` or `blue`.
+    Stamp,
+    /// The grid is not 5×5 or 7×7 with `o` in the center and `.`, `x` or `s`
+    /// elsewhere.
+    Grid,
+    /// An offset is not `file,rank`.
+    Offset,
+    /// An offset is off a board of that size.
+    Range(isize, isize, Size),
+    /// A move does not move.
+    Origin,
+    /// The card has no move.
+    NoMoves,
+    /// The card or one of its moves appears twice.
+    Duplicate,
+}
+
+impl Display for CardError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "{}", error),
+            Self::Invalid { line, kind } => write!(f, "line {}: {}", line, kind),
+        }
+    }
+}
+
+impl Display for CardErrorKind {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match *self {
+            Self::Header => write!(f, "expected `<name> <red|blue>`"),
+            Self::Stamp => write!(f, "stamp must be `red` or `blue`"),
+            Self::Grid => write!(f, "expected a 5x5 or 7x7 grid of `o`, `x`, `s` or `.`"),
+            Self::Offset => write!(f, "expected `file,rank` offsets"),
+            Self::Range(file, rank, size) => write!(
+                f,
+                "offset {},{} is out of ±{} on {3}x{3}",
+                file,
+                rank,
+                size.get() / 2,
+                size.get()
+            ),
+            Self::Origin => write!(f, "a move cannot be 0,0"),
+            Self::NoMoves => write!(f, "a card needs at least one move"),
+            Self::Duplicate => write!(f, "duplicate card or move"),
+        }
+    }
+}
+
+impl Error for CardError {}
+
+/// Why a tablebase file was rejected by `Tablebase::load`.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum TablebaseError {
+    /// The file could not be read or written.
+    Io(String),
+    /// The file is not a tablebase.
+    Format,
+    /// The tablebase is for a card missing from the card set.
+    Card(String),
+}
+
+impl Display for TablebaseError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "{}", error),
+            Self::Format => write!(f, "not a tablebase"),
+            Self::Card(name) => write!(f, "unknown card {}", name),
+        }
+    }
+}
+
+impl Error for TablebaseError {}
+
+/// Why an engine failed to play (see `ai::External`).
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum EngineError {
+    /// The engine program could not be talked to, or exited.
+    Io(String),
+    /// The engine replied `error` with that message.
+    Reply(String),
+    /// The engine's `bestmove` is not a legal play.
+    Play { play: String, error: String },
+}
+
+impl Display for EngineError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "{}", error),
+            Self::Reply(message) => write!(f, "error {}", message),
+            Self::Play { play, error } => write!(f, "bestmove {}: {}", play, error),
+        }
+    }
+}
+
+impl Error for EngineError {}
+
+impl From<io::Error> for EngineError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error.to_string())
+    }
+}
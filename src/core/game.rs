@@ -1,22 +1,36 @@
 use super::*;
+use std::hash::Hash;
+use std::hash::Hasher;
 use std::ops::Index;
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub struct Side {
-    pub pieces: [Option<Square>; SIZE],
-    pub cards:  [usize; HAND],
+    pub pieces:   [Option<Square>; Piece::ALL.len()],
+    pub cards:    [usize; HAND],
+    /// The squares of `pieces`.
+    pub occupied: Bitboard,
 }
 
 impl Side {
-    pub fn new(player: Player, cards: [usize; HAND]) -> Self {
-        macro_rules! pieces {
-            ($rank:ident $($file:ident)*) => { [$(Some(Square($file, $rank))),*] };
+    /// `player`'s side at the start of a game on a board of `size`.
+    pub fn new(player: Player, cards: [usize; HAND], size: Size) -> Self {
+        let mut side = Self::empty(cards);
+
+        for piece in size.pieces() {
+            let square = Square(piece.file(size), size.home(player));
+            *side.square_mut(piece) = Some(square);
+            side.occupied |= Bitboard::from(square);
         }
 
-        let rank = player.rank();
+        side
+    }
+
+    /// A side with no pieces.
+    pub fn empty(cards: [usize; HAND]) -> Self {
         Self {
-            pieces: pieces!(rank A B C D E),
+            pieces: [None; Piece::ALL.len()],
             cards,
+            occupied: Bitboard::EMPTY,
         }
     }
 
@@ -24,22 +38,12 @@ impl Side {
         self.pieces
             .into_iter()
             .enumerate()
-            .filter_map(|(i, square)| {
-                if let Some(square) = square {
-                    Some((i, square))
-                } else {
-                    None
-                }
-            })
+            .filter_map(|(i, square)| square.map(|square| (i, square)))
             .map(move |(i, square)| (Piece::from(i), square))
     }
 
     pub fn squares(&self) -> impl '_ + Iterator<Item = Square> {
-        self.pieces.into_iter().filter_map(|square| square)
-    }
-
-    pub fn cards(&self) -> [Card; HAND] {
-        [CARDS[self.cards[0]], CARDS[self.cards[1]]]
+        self.pieces.into_iter().flatten()
     }
 
     pub fn square(&self, piece: Piece) -> &Option<Square> {
@@ -58,38 +62,199 @@ pub enum Play {
         src:  Square,
         dest: Square,
     },
+    /// Moves the Wind Spirit with a card (Way of the Wind).
+    Spirit {
+        card: usize,
+        dest: Square,
+    },
+    /// Moves a piece, then the Wind Spirit to `spirit`, with a wind card.
+    Wind {
+        card:   usize,
+        src:    Square,
+        dest:   Square,
+        spirit: Square,
+    },
     Discard(usize),
 }
 
+impl Play {
+    pub fn card(&self) -> usize {
+        match *self {
+            Self::Card { card, .. }
+            | Self::Spirit { card, .. }
+            | Self::Wind { card, .. }
+            | Self::Discard(card) => card,
+        }
+    }
+}
+
+/// Inline up to `MAX_PLAYS`, which cards with more moves than `CARDS`'
+/// (see `Card::load`) can exceed.
+pub type Plays = SpillList<Play, MAX_PLAYS>;
+
+/// Only there to fill `Plays`' free slots.
+impl Default for Play {
+    fn default() -> Self {
+        Self::Discard(0)
+    }
+}
+
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub enum State {
     Turn(Player),
     Won(Player),
-    Draw,
+    Draw(DrawRule),
 }
 
-#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[derive(Clone, Eq, PartialEq, Debug)]
 pub struct Game {
-    state: State,
-    board: Board,
-    red:   Side,
-    blue:  Side,
-    spare: usize,
-    timer: u8,
+    cards:    Cards,
+    state:    State,
+    board:    Board,
+    red:      Side,
+    blue:     Side,
+    spare:    usize,
+    timer:    u8,
+    rules:    Rules,
+    spirit:   Option<Square>,
+    hidden:   Bitboard,
+    /// The squares where the hidden ninjas may be, as far as their opponent
+    /// knows: a superset of `hidden`.
+    suspects: Bitboard,
+    key:      u64,
+    history:  History,
+    /// The mirror image of each dealt card, if all are dealt.
+    mirrors:  Option<[(usize, usize); Deal::LEN]>,
 }
 
 impl Game {
-    const DRAW_LIMIT: u8 = 40;
+    pub fn new(red: [usize; HAND], blue: [usize; HAND], spare: usize, rules: Rules) -> Self {
+        Self::with_cards(Card::builtin(), Deal { red, blue, spare }, rules)
+    }
 
-    pub fn new(red: [usize; HAND], blue: [usize; HAND], spare: usize) -> Self {
-        Self {
-            state: State::Turn(CARDS[spare].stamp),
-            board: Board::default(),
-            red: Side::new(Red, red),
-            blue: Side::new(Blue, blue),
+    /// A game dealt from `cards` (e.g. loaded with `Card::load`) instead of
+    /// `CARDS`.
+    pub fn with_cards(cards: Cards, deal: Deal, rules: Rules) -> Self {
+        let Deal { red, blue, spare } = deal;
+        let mut game = Self {
+            state: State::Turn(cards[spare].stamp),
+            board: Board::new(rules.size),
+            red: Side::new(Red, red, rules.size),
+            blue: Side::new(Blue, blue, rules.size),
             spare,
-            timer: Self::DRAW_LIMIT,
+            timer: rules.draws.limit(),
+            rules,
+            spirit: rules.wind.then_some(rules.size.center()),
+            hidden: Bitboard::EMPTY,
+            suspects: Bitboard::EMPTY,
+            key: 0,
+            history: History::default(),
+            mirrors: deal.mirrors(&cards),
+            cards,
+        };
+
+        if let Some(shadow) = rules.shadow {
+            game.hidden = game[shadow].occupied & !Bitboard::from(rules.size.temple(shadow));
+            game.suspects = game.hidden;
         }
+
+        game.key = game.zobrist();
+        game
+    }
+
+    pub fn from_deal(deal: Deal, rules: Rules) -> Self {
+        Self::new(deal.red, deal.blue, deal.spare, rules)
+    }
+
+    /// A game with a `Deal::random` deal.
+    pub fn random(seed: Seed, rules: Rules) -> Self {
+        Self::from_deal(Deal::random(seed), rules)
+    }
+
+    /// The current position (without history).
+    pub fn position(&self) -> Position {
+        Position {
+            cards:  self.cards.clone(),
+            size:   self.rules.size,
+            board:  self.board,
+            deal:   Deal {
+                red:   self.red.cards,
+                blue:  self.blue.cards,
+                spare: self.spare,
+            },
+            state:  self.state,
+            timer:  self.timer,
+            spirit: self.spirit,
+            hidden: self.hidden,
+        }
+    }
+
+    /// A game starting from `position`, with an empty history (see also
+    /// `GameBuilder`).
+    pub fn from_position(position: Position, rules: Rules) -> Result<Self, PositionError> {
+        let Position {
+            cards,
+            size,
+            board,
+            deal,
+            state,
+            timer,
+            spirit,
+            hidden,
+        } = position;
+
+        if size != rules.size {
+            return Err(PositionError::Size);
+        }
+
+        let mut dealt = deal.cards().to_vec();
+        dealt.sort_unstable();
+        dealt.dedup();
+        if dealt.len() != Deal::LEN || dealt.iter().any(|&card| card >= cards.len()) {
+            return Err(PositionError::Cards);
+        }
+
+        let mut game = Self {
+            state,
+            board: Board::EMPTY,
+            red: Side::empty(deal.red),
+            blue: Side::empty(deal.blue),
+            spare: deal.spare,
+            timer,
+            rules,
+            spirit,
+            hidden,
+            suspects: hidden,
+            key: 0,
+            history: History::default(),
+            mirrors: deal.mirrors(&cards),
+            cards,
+        };
+
+        for square in (0..SQUARES).map(Square::from) {
+            let Some((player, piece)) = board[square] else {
+                continue;
+            };
+            if !size.area().contains(square) {
+                return Err(PositionError::Board);
+            }
+            if !size.pieces().any(|p| p == piece) {
+                return Err(PositionError::Pieces(player));
+            }
+
+            let side = game.side_mut(player);
+            if side.square(piece).is_some() {
+                return Err(PositionError::Duplicate(player, piece));
+            }
+
+            *side.square_mut(piece) = Some(square);
+            side.occupied |= Bitboard::from(square);
+            game.board[square] = Some((player, piece));
+        }
+
+        game.check_position()?;
+        game.key = game.zobrist();
+        Ok(game)
     }
 
     pub fn state(&self) -> State {
@@ -110,44 +275,246 @@ impl Game {
         }
     }
 
-    pub fn spare(&self) -> Card {
-        CARDS[self.spare]
+    /// The cards the deal indices refer to.
+    pub fn card_set(&self) -> &Cards {
+        &self.cards
+    }
+
+    pub fn spare(&self) -> &Card {
+        &self.cards[self.spare]
+    }
+
+    pub fn cards(&self, player: Player) -> [&Card; HAND] {
+        self[player].cards.map(|card| &self.cards[card])
+    }
+
+    pub fn card(&self, player: Player, card: usize) -> &Card {
+        &self.cards[self[player].cards[card]]
+    }
+
+    pub fn history(&self) -> &History {
+        &self.history
+    }
+
+    /// The Wind Spirit's square (Way of the Wind).
+    pub fn spirit(&self) -> Option<Square> {
+        self.spirit
+    }
+
+    /// The squares of the ninjas hidden from the opponent (Light and Shadow).
+    pub fn hidden(&self) -> Bitboard {
+        self.hidden
+    }
+
+    /// The squares where the hidden ninjas may be, as far as their opponent
+    /// knows from the plays (the cards played, the captures, where its own
+    /// pieces went).
+    pub fn suspects(&self) -> Bitboard {
+        self.suspects
+    }
+
+    /// The board as `player` sees it, without the opponent's hidden ninjas.
+    pub fn view(&self, player: Player) -> Board {
+        let mut board = self.board;
+
+        for square in self.hidden.squares() {
+            if matches!(board[square], Some((p, _)) if p != player) {
+                board[square] = None;
+            }
+        }
+
+        board
+    }
+
+    /// A game `player` cannot tell apart from this one: the opponent's hidden
+    /// ninjas are moved to random `suspects` squares, so that engines cannot
+    /// see them. The game itself if `player` sees the whole board, otherwise
+    /// without history.
+    pub fn determinize(&self, player: Player, rng: &mut Rng) -> Self {
+        if self.rules.shadow != Some(!player) || self.hidden.is_empty() {
+            return self.clone();
+        }
+
+        let mut position = self.position();
+        let mut ninjas = self
+            .hidden
+            .squares()
+            .map(|square| position.board[square].take().unwrap())
+            .collect::<Vec<_>>();
+        let mut squares = self.suspects.squares().collect::<Vec<_>>();
+        rng.shuffle(&mut ninjas);
+        rng.shuffle(&mut squares);
+
+        position.hidden = Bitboard::EMPTY;
+        for (ninja, square) in ninjas.into_iter().zip(squares) {
+            position.board[square] = Some(ninja);
+            position.hidden |= Bitboard::from(square);
+        }
+
+        let mut game = Self::from_position(position, self.rules).unwrap();
+        game.suspects = self.suspects;
+        game
+    }
+
+    /// The Zobrist key of the position: pieces, hands, spare, hidden ninjas and
+    /// state (side to move, or the end of the game).
+    pub fn key(&self) -> u64 {
+        self.key
+    }
+
+    /// The Zobrist key of the left-right mirror image of the position (files
+    /// A↔E, each card swapped with its mirror image), if the mirrors of the
+    /// dealt cards are dealt too.
+    pub fn mirror_key(&self) -> Option<u64> {
+        self.mirrors.map(|_| {
+            self.zobrist_with(
+                |square| square.mirror(self.size()),
+                |card| self.mirror_card(card),
+            )
+        })
+    }
+
+    /// The mirror image of the dealt `card` (see `mirror_key`).
+    fn mirror_card(&self, card: usize) -> usize {
+        self.mirrors
+            .into_iter()
+            .flatten()
+            .find_map(|(c, mirror)| (c == card).then_some(mirror))
+            .unwrap()
+    }
+
+    /// The smaller of `key` and `mirror_key`, with the draw timer (as in
+    /// `key_with_timer`), and whether it is the mirror image's. Mirrored
+    /// positions share a canonical key, and plays of one are `Play::mirror`s
+    /// of the other's.
+    pub fn canonical_key(&self) -> (u64, bool) {
+        let (key, mirrored) = match self.mirror_key() {
+            Some(mirror) if mirror < self.key => (mirror, true),
+            _ => (self.key, false),
+        };
+
+        (key ^ zobrist::timer(self.timer), mirrored)
+    }
+
+    /// `play` as in the position of `canonical_key` (mirrored if `mirrored`),
+    /// its card being the card itself, an index in `card_set`: keys do not
+    /// depend on the order of the hands, so hand indices may not carry over.
+    pub fn to_canonical(&self, play: Play, mirrored: bool) -> Play {
+        let card = self[self.player().unwrap()].cards[play.card()];
+
+        if mirrored {
+            play.mirror(self.size()).with_card(self.mirror_card(card))
+        } else {
+            play.with_card(card)
+        }
+    }
+
+    /// The play that `to_canonical` makes `play` of, if its card is in the
+    /// hand of the player to move.
+    pub fn from_canonical(&self, play: Play, mirrored: bool) -> Option<Play> {
+        let (play, card) = if mirrored {
+            (play.mirror(self.size()), self.mirror_card(play.card()))
+        } else {
+            (play, play.card())
+        };
+        let hand = self[self.player()?].cards;
+
+        Some(play.with_card(hand.iter().position(|&c| c == card)?))
+    }
+
+    /// The number of plies left before a draw by `DrawRule::Timer`.
+    pub fn timer(&self) -> u8 {
+        self.timer
+    }
+
+    pub fn rules(&self) -> Rules {
+        self.rules
+    }
+
+    pub fn size(&self) -> Size {
+        self.rules.size
+    }
+
+    /// How many times the current position occurred, counting this one.
+    pub fn repetitions(&self) -> usize {
+        1 + self
+            .history
+            .past()
+            .iter()
+            .rev()
+            .take_while(|record| record.capture.is_none())
+            .filter(|record| record.key == self.key)
+            .count()
+    }
+
+    /// `Game::key`, also keyed by the draw timer.
+    pub fn key_with_timer(&self) -> u64 {
+        self.key ^ zobrist::timer(self.timer)
     }
 
     pub fn pieces(&self, player: Player) -> impl '_ + Iterator<Item = (Piece, Square)> {
         self[player].pieces()
     }
 
-    pub fn distance(&self, player: Player) -> u8 {
-        let king = self[(player, King)].unwrap();
-        let square = Square::king(!player);
+    /// How many king moves `player`'s king is from the opponent's temple, if
+    /// it is still on the board (it can be captured without the Way of the
+    /// Stone).
+    pub fn distance(&self, player: Player) -> Option<u8> {
+        let king = self[(player, King)]?;
+        let square = self.size().temple(!player);
 
         let king = (king.file() as i8, king.rank() as i8);
         let square = (square.file() as i8, square.rank() as i8);
 
         let distance = ((king.0 - square.0).abs(), (king.1 - square.1).abs());
-        distance.0.max(distance.1) as u8
+        Some(distance.0.max(distance.1) as u8)
     }
 
     pub fn plays(&self) -> Vec<Play> {
-        let mut plays = vec![];
+        self.legal_plays().to_vec()
+    }
+
+    pub fn legal_plays(&self) -> Plays {
+        let mut plays = Plays::default();
+        self.plays_into(&mut plays);
+        plays
+    }
+
+    /// Replaces the content of `plays` with the legal plays.
+    pub fn plays_into(&self, plays: &mut Plays) {
         let player = self.player().unwrap();
-        let flipper = player.flipper();
         let side = self.side(player);
-        let cards = side.cards();
-        let squares = side.squares();
-        let is_valid = move |square| !matches!(self[square], Some((p, _)) if p == player);
-
-        for src in squares {
-            for (c, card) in cards.iter().enumerate() {
-                for mov in card.moves.iter().map(flipper) {
-                    if let Some(dest) = src.apply(mov) {
-                        if is_valid(dest) {
-                            plays.push(Play::Card { card: c, src, dest });
+        let free = !side.occupied & !self.spirit_bitboard();
+
+        plays.clear();
+
+        for card in 0..HAND {
+            let wind = self.is_wind(card);
+
+            for src in side.occupied.squares() {
+                for dest in
+                    (self.card(player, card).dests(player, src, self.size()) & free).squares()
+                {
+                    if wind {
+                        for spirit in self.spirit_dests(card, src, dest) {
+                            plays.push(Play::Wind {
+                                card,
+                                src,
+                                dest,
+                                spirit,
+                            });
                         }
+                    } else {
+                        plays.push(Play::Card { card, src, dest });
                     }
                 }
             }
+
+            if let (Some(spirit), false) = (self.spirit, wind) {
+                for dest in self.dests(card, spirit) {
+                    plays.push(Play::Spirit { card, dest });
+                }
+            }
         }
 
         if plays.is_empty() {
@@ -155,47 +522,218 @@ impl Game {
                 plays.push(Play::Discard(card));
             }
         }
-
-        plays
     }
 
+    /// The squares the piece (or the Wind Spirit) on `src` can move to with
+    /// `card`.
     pub fn dests(&self, card: usize, src: Square) -> impl '_ + Iterator<Item = Square> {
         let player = self.player().unwrap();
         let side = self.side(player);
-        let card = side.cards()[card];
-        let mut moves = card.moves.iter().map(player.flipper());
+        let dests = self.card(player, card).dests(player, src, self.size());
 
-        let has_piece = move |square| matches!(self[square], Some((p, _)) if p == player);
-        debug_assert!(has_piece(src));
+        if self.spirit == Some(src) {
+            (dests & !self.kings()).squares()
+        } else {
+            debug_assert!(side.occupied.contains(src));
+            (dests & !side.occupied & !self.spirit_bitboard()).squares()
+        }
+    }
 
-        std::iter::from_fn(move || loop {
-            if let Some(dest) = src.apply(moves.next()?).filter(|&dest| !has_piece(dest)) {
-                return Some(dest);
+    /// The squares the Wind Spirit can move to with the wind `card` once the
+    /// piece on `src` moved to `dest`.
+    pub fn spirit_dests(
+        &self,
+        card: usize,
+        src: Square,
+        dest: Square,
+    ) -> impl '_ + Iterator<Item = Square> {
+        let player = self.player().unwrap();
+        let card = self.card(player, card);
+        let kings = self.kings() & !Bitboard::from(src) & !Bitboard::from(dest);
+        let kings = if self[src] == Some((player, King)) {
+            kings | Bitboard::from(dest)
+        } else {
+            kings
+        };
+
+        self.spirit
+            .map_or(Bitboard::EMPTY, |spirit| {
+                card.spirit_dests(player, spirit, self.size()) & !kings
+            })
+            .squares()
+    }
+
+    /// Whether `card` is a wind card and the Wind Spirit is in play.
+    pub fn is_wind(&self, card: usize) -> bool {
+        self.spirit.is_some() && self.card(self.player().unwrap(), card).is_wind()
+    }
+
+    /// Checks that `play` is legal in the current position.
+    pub fn check(&self, play: Play) -> Result<(), PlayError> {
+        let player = match self.state {
+            State::Turn(player) => player,
+            state => return Err(PlayError::Over(state)),
+        };
+
+        let check_piece = |card, src, dest| match self[src] {
+            None => Err(PlayError::Empty(src)),
+            Some((p, _)) if p != player => Err(PlayError::Opponent(src)),
+            _ if self.dests(card, src).any(|square| square == dest) => Ok(()),
+            _ => Err(PlayError::Unreachable(dest)),
+        };
+
+        match play {
+            _ if play.card() >= HAND => Err(PlayError::Card(play.card())),
+            Play::Card { card, .. } | Play::Spirit { card, .. } if self.is_wind(card) =>
+                Err(PlayError::Wind),
+            Play::Card { card, src, dest } => check_piece(card, src, dest),
+            Play::Spirit { card, dest } => match self.spirit {
+                Some(spirit) if self.dests(card, spirit).any(|square| square == dest) => Ok(()),
+                _ => Err(PlayError::Spirit(dest)),
+            },
+            Play::Wind {
+                card,
+                src,
+                dest,
+                spirit,
+            } => {
+                check_piece(card, src, dest)?;
+
+                if self.is_wind(card) && self.spirit_dests(card, src, dest).any(|s| s == spirit) {
+                    Ok(())
+                } else {
+                    Err(PlayError::Spirit(spirit))
+                }
             }
-        })
+            Play::Discard(_) =>
+                if self.legal_plays().contains(&play) {
+                    Ok(())
+                } else {
+                    Err(PlayError::Discard)
+                },
+        }
+    }
+
+    pub fn try_play(&mut self, play: Play) -> Result<State, PlayError> {
+        self.check(play)?;
+        Ok(self.play(play))
     }
 
+    /// Plays `play`, which must be legal (see `Game::try_play`).
     pub fn play(&mut self, play: Play) -> State {
-        let player = self.player().unwrap();
+        self.history.clear_future();
+        self.apply(play)
+    }
 
-        let (capture, discard) = match play {
-            Play::Card { card, src, dest } => {
-                let (_, piece) = self[src].unwrap();
-                let capture = self[dest];
+    pub fn undo(&mut self) -> Option<Play> {
+        let record = self.history.pop()?;
+        self.revert(record);
+        self.history.push_future(record.play);
+
+        Some(record.play)
+    }
+
+    pub fn redo(&mut self) -> Option<State> {
+        let play = self.history.pop_future()?;
+
+        Some(self.apply(play))
+    }
+}
+
+impl Game {
+    fn side_mut(&mut self, player: Player) -> &mut Side {
+        match player {
+            Red => &mut self.red,
+            Blue => &mut self.blue,
+        }
+    }
+
+    fn check_position(&self) -> Result<(), PositionError> {
+        let rules = self.rules;
+
+        for player in [Red, Blue] {
+            let king = self[(player, King)];
 
-                // Update board
-                self.board[src] = None;
-                self.board[dest] = Some((player, piece));
+            if king.is_none() && rules.stone && self.state != State::Won(!player) {
+                return Err(PositionError::Kings(player));
+            }
+            if rules.stream
+                && king == Some(self.size().temple(!player))
+                && self.state != State::Won(player)
+            {
+                return Err(PositionError::State);
+            }
+        }
+
+        if rules.wind != self.spirit.is_some()
+            || self
+                .spirit
+                .is_some_and(|spirit| self[spirit].is_some() || !rules.size.area().contains(spirit))
+        {
+            return Err(PositionError::Spirit);
+        }
 
-                // Update pieces
-                *self.side_mut(player).square_mut(piece) = Some(dest);
+        let ninjas = match rules.shadow {
+            Some(shadow) =>
+                self[shadow].occupied
+                    & !self[(shadow, King)].map_or(Bitboard::EMPTY, Bitboard::from),
+            None => Bitboard::EMPTY,
+        };
+        if !(self.hidden & !ninjas).is_empty() {
+            return Err(PositionError::Hidden);
+        }
 
+        if self.timer > rules.draws.limit() {
+            return Err(PositionError::Timer);
+        }
+
+        Ok(())
+    }
+
+    fn apply(&mut self, play: Play) -> State {
+        let player = self.player().unwrap();
+        let key = self.key;
+        let spirit = self.spirit;
+        let hidden = self.hidden;
+        let suspects = self.suspects;
+
+        let (capture, discard) = match play {
+            Play::Card { card, src, dest } => (self.move_piece(player, src, dest), card),
+            Play::Spirit { card, dest } => {
+                self.move_spirit(dest);
+                (None, card)
+            }
+            Play::Wind {
+                card,
+                src,
+                dest,
+                spirit,
+            } => {
+                let capture = self.move_piece(player, src, dest);
+                self.move_spirit(spirit);
                 (capture, card)
             }
             Play::Discard(card) => (None, card),
         };
 
+        let record = Record {
+            play,
+            capture,
+            spare: self.spare,
+            timer: self.timer,
+            state: self.state,
+            key,
+            spirit,
+            hidden,
+            suspects,
+        };
+        self.update_suspects(&record);
+        self.history.push(record);
+
         // Update hand
+        let card = self.side(player).cards[discard];
+        self.key ^= zobrist::hand(player, card) ^ zobrist::spare(self.spare);
+        self.key ^= zobrist::hand(player, self.spare) ^ zobrist::spare(card);
         std::mem::swap(&mut self.spare, {
             &mut match player {
                 Red => &mut self.red,
@@ -203,36 +741,258 @@ impl Game {
             }
             .cards[discard]
         });
+        self.key ^= zobrist::state(self.state) ^ zobrist::state(State::Turn(!player));
 
         // Update timer
         if capture.is_none() {
-            self.timer -= 1;
+            self.timer = self.timer.saturating_sub(1);
         } else {
-            self.timer = Self::DRAW_LIMIT;
+            self.timer = self.rules.draws.limit();
         }
 
         // Update state
-        let stone = capture == Some((!player, King));
-        let stream = self[Square::king(!player)] == Some((player, King));
+        let stone = self.rules.stone && capture == Some((!player, King));
+        let stream = self.rules.stream && self[self.size().temple(!player)] == Some((player, King));
+        let discard = self.rules.discard == Discard::Loss && matches!(play, Play::Discard(_));
+        let draws = self.rules.draws;
 
         self.state = if stone || stream {
             State::Won(player)
-        } else if self.timer == 0 {
-            State::Draw
+        } else if discard {
+            State::Won(!player)
+        } else if draws.timer.is_some() && self.timer == 0 {
+            State::Draw(DrawRule::Timer)
+        } else if matches!(draws.repetition, Some(n) if self.repetitions() >= n as usize) {
+            State::Draw(DrawRule::Repetition)
         } else {
             State::Turn(!player)
         };
+        self.key ^= zobrist::state(State::Turn(!player)) ^ zobrist::state(self.state);
 
         self.state
     }
-}
 
-impl Game {
-    fn side_mut(&mut self, player: Player) -> &mut Side {
-        match player {
-            Red => &mut self.red,
-            Blue => &mut self.blue,
+    fn revert(&mut self, record: Record) {
+        let player = record.player();
+
+        if let Some(spirit) = record.spirit {
+            self.unmove_spirit(spirit);
         }
+
+        let discard = match record.play {
+            Play::Card { card, src, dest }
+            | Play::Wind {
+                card, src, dest, ..
+            } => {
+                self.unmove_piece(player, src, dest, record.capture);
+                card
+            }
+            Play::Spirit { card, .. } | Play::Discard(card) => card,
+        };
+
+        // Update hand
+        std::mem::swap(&mut self.spare, {
+            &mut match player {
+                Red => &mut self.red,
+                Blue => &mut self.blue,
+            }
+            .cards[discard]
+        });
+        debug_assert_eq!(self.spare, record.spare);
+
+        self.timer = record.timer;
+        self.state = record.state;
+        self.key = record.key;
+        self.hidden = record.hidden;
+        self.suspects = record.suspects;
+    }
+
+    /// Moves `player`'s piece from `src` to `dest`, returning the capture.
+    fn move_piece(&mut self, player: Player, src: Square, dest: Square) -> Option<(Player, Piece)> {
+        let (_, piece) = self[src].unwrap();
+        let capture = self[dest];
+
+        // Update board
+        self.board[src] = None;
+        self.board[dest] = Some((player, piece));
+        self.key ^= zobrist::piece(player, piece, src);
+        self.key ^= zobrist::piece(player, piece, dest);
+
+        // Update pieces
+        let side = self.side_mut(player);
+        *side.square_mut(piece) = Some(dest);
+        side.occupied ^= Bitboard::from(src) | Bitboard::from(dest);
+
+        if let Some((_, captured)) = capture {
+            let side = self.side_mut(!player);
+            *side.square_mut(captured) = None;
+            side.occupied ^= Bitboard::from(dest);
+            self.key ^= zobrist::piece(!player, captured, dest);
+        }
+
+        // Update ninjas, revealed when they capture or are captured
+        let mut hidden = self.hidden & !Bitboard::from(src) & !Bitboard::from(dest);
+        if self.hidden.contains(src) && capture.is_none() {
+            hidden |= Bitboard::from(dest);
+        }
+        self.set_hidden(hidden);
+
+        capture
+    }
+
+    /// Takes back `move_piece`. Does not update the key nor the ninjas.
+    fn unmove_piece(
+        &mut self,
+        player: Player,
+        src: Square,
+        dest: Square,
+        capture: Option<(Player, Piece)>,
+    ) {
+        let (_, piece) = self[dest].unwrap();
+
+        // Update board
+        self.board[dest] = capture;
+        self.board[src] = Some((player, piece));
+
+        // Update pieces
+        let side = self.side_mut(player);
+        *side.square_mut(piece) = Some(src);
+        side.occupied ^= Bitboard::from(src) | Bitboard::from(dest);
+
+        if let Some((_, captured)) = capture {
+            let side = self.side_mut(!player);
+            *side.square_mut(captured) = Some(dest);
+            side.occupied ^= Bitboard::from(dest);
+        }
+    }
+
+    /// Moves the Wind Spirit to `dest`, swapping with the student there.
+    fn move_spirit(&mut self, dest: Square) {
+        let src = self.spirit.unwrap();
+
+        self.spirit = Some(dest);
+        self.key ^= zobrist::spirit(src) ^ zobrist::spirit(dest);
+
+        if let Some((player, piece)) = self[dest] {
+            debug_assert!(piece != King);
+
+            self.board[dest] = None;
+            self.board[src] = Some((player, piece));
+            self.key ^= zobrist::piece(player, piece, dest);
+            self.key ^= zobrist::piece(player, piece, src);
+
+            let side = self.side_mut(player);
+            *side.square_mut(piece) = Some(src);
+            side.occupied ^= Bitboard::from(src) | Bitboard::from(dest);
+
+            if self.hidden.contains(dest) {
+                self.set_hidden(self.hidden ^ Bitboard::from(src) ^ Bitboard::from(dest));
+            }
+        }
+    }
+
+    /// Moves the Wind Spirit back to `src`, swapping back the student that
+    /// `move_spirit` moved there. Does not update the key nor the ninjas.
+    fn unmove_spirit(&mut self, src: Square) {
+        let dest = self.spirit.unwrap();
+
+        self.spirit = Some(src);
+
+        if let Some((player, piece)) = self[src] {
+            self.board[src] = None;
+            self.board[dest] = Some((player, piece));
+
+            let side = self.side_mut(player);
+            *side.square_mut(piece) = Some(dest);
+            side.occupied ^= Bitboard::from(src) | Bitboard::from(dest);
+        }
+    }
+
+    /// Sets the hidden ninjas, updating the key.
+    fn set_hidden(&mut self, hidden: Bitboard) {
+        for square in (self.hidden ^ hidden).squares() {
+            self.key ^= zobrist::hidden(square);
+        }
+        self.hidden = hidden;
+    }
+
+    /// Narrows or widens `suspects` after the play of `record` (before the
+    /// hands are updated): a hidden ninja's move spreads them by the card's
+    /// moves, the Wind Spirit moving onto one leaves one behind, and squares
+    /// in sight are cleared.
+    fn update_suspects(&mut self, record: &Record) {
+        let Some(shadow) = self.rules.shadow else {
+            return;
+        };
+
+        if record.is_hidden() {
+            let card = self.card(shadow, record.play.card());
+            let size = self.size();
+            let dests = self
+                .suspects
+                .squares()
+                .fold(Bitboard::EMPTY, |dests, square| {
+                    dests | card.dests(shadow, square, size)
+                });
+            self.suspects |= dests;
+        }
+        if let (Some(src), Some(dest)) = (record.spirit, self.spirit) {
+            if src != dest && self.suspects.contains(dest) {
+                self.suspects |= Bitboard::from(src);
+            }
+        }
+
+        let visible = (self.red.occupied | self.blue.occupied) & !self.hidden;
+        self.suspects &= !visible & !self.spirit_bitboard();
+        debug_assert!((self.hidden & !self.suspects).is_empty());
+    }
+
+    fn spirit_bitboard(&self) -> Bitboard {
+        self.spirit.map_or(Bitboard::EMPTY, Bitboard::from)
+    }
+
+    fn kings(&self) -> Bitboard {
+        [Red, Blue]
+            .into_iter()
+            .filter_map(|player| self[(player, King)])
+            .collect()
+    }
+
+    fn zobrist(&self) -> u64 {
+        self.zobrist_with(|square| square, |card| card)
+    }
+
+    /// The Zobrist key of the position with squares and cards mapped.
+    fn zobrist_with(
+        &self,
+        square: impl Fn(Square) -> Square,
+        card: impl Fn(usize) -> usize,
+    ) -> u64 {
+        let mut key = zobrist::spare(card(self.spare));
+
+        for player in [Red, Blue] {
+            for (piece, src) in self.pieces(player) {
+                key ^= zobrist::piece(player, piece, square(src));
+            }
+            for hand in self[player].cards {
+                key ^= zobrist::hand(player, card(hand));
+            }
+        }
+
+        if let Some(spirit) = self.spirit {
+            key ^= zobrist::spirit(square(spirit));
+        }
+        for hidden in self.hidden.squares() {
+            key ^= zobrist::hidden(square(hidden));
+        }
+
+        key ^ zobrist::state(self.state)
+    }
+}
+
+impl Hash for Game {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key.hash(state);
     }
 }
 
@@ -259,3 +1019,120 @@ impl Index<(Player, Piece)> for Game {
         self.side(player).square(piece)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Plays random games, undoing and redoing plays on the way, checking the
+    /// incremental key against `Game::zobrist` after each step.
+    #[test]
+    fn keys() {
+        let mut rng = Rng::new(Seed(0));
+        let rules = [
+            Rules::default(),
+            Rules {
+                draws: Draws::both(12, 2),
+                ..Rules::default()
+            },
+            Rules {
+                wind: true,
+                ..Rules::default()
+            },
+            Rules {
+                shadow: Some(Blue),
+                ..Rules::default()
+            },
+        ];
+
+        for rules in rules {
+            for _ in 0..50 {
+                let mut game = Game::random(Seed(rng.next_u64()), rules);
+                assert_eq!(game.key(), game.zobrist());
+
+                while game.player().is_some() {
+                    let plays = game.legal_plays();
+                    game.play(plays[rng.below(plays.len())]);
+                    assert_eq!(game.key(), game.zobrist());
+
+                    if rng.below(4) == 0 {
+                        let key = game.key();
+
+                        game.undo();
+                        assert_eq!(game.key(), game.zobrist());
+                        game.redo();
+                        assert_eq!(game.key(), key);
+                    }
+                }
+
+                while game.undo().is_some() {
+                    assert_eq!(game.key(), game.zobrist());
+                }
+            }
+        }
+    }
+
+    /// Light's engines see a game that looks the same to Light, the ninjas
+    /// moved among the suspects.
+    #[test]
+    fn determinize() {
+        let mut rng = Rng::new(Seed(1));
+        let rules = Rules {
+            shadow: Some(Blue),
+            ..Rules::default()
+        };
+
+        for _ in 0..50 {
+            let mut game = Game::random(Seed(rng.next_u64()), rules);
+
+            while game.player().is_some() {
+                let seen = game.determinize(Red, &mut rng);
+                assert_eq!(seen.view(Red), game.view(Red));
+                assert_eq!(seen.hidden().len(), game.hidden().len());
+                assert!((seen.hidden() & !game.suspects()).is_empty());
+                assert_eq!(game.determinize(Blue, &mut rng), game);
+
+                let plays = game.legal_plays();
+                game.play(plays[rng.below(plays.len())]);
+                assert!((game.hidden() & !game.suspects()).is_empty());
+            }
+        }
+    }
+
+    /// Canonical plays carry over between positions of the same canonical
+    /// key, whatever the order of the hands.
+    #[test]
+    fn canonical_plays() {
+        let mut rng = Rng::new(Seed(2));
+        let cards = Card::builtin();
+        let deal = Deal::parse("Frog,Rabbit,Crab,Tiger,Monkey", &cards).unwrap();
+
+        for _ in 0..20 {
+            let mut game = Game::with_cards(cards.clone(), deal, Rules::default());
+
+            while game.player().is_some() {
+                let (key, mirrored) = game.canonical_key();
+                let mut position = game.position();
+                position.deal.red.reverse();
+                position.deal.blue.reverse();
+                let swapped = Game::from_position(position, game.rules()).unwrap();
+                assert_eq!(swapped.canonical_key(), (key, mirrored));
+
+                for play in game.plays() {
+                    let canonical = game.to_canonical(play, mirrored);
+                    assert_eq!(game.from_canonical(canonical, mirrored), Some(play));
+                    assert_eq!(
+                        swapped.to_canonical(
+                            swapped.from_canonical(canonical, mirrored).unwrap(),
+                            mirrored
+                        ),
+                        canonical
+                    );
+                }
+
+                let plays = game.legal_plays();
+                game.play(plays[rng.below(plays.len())]);
+            }
+        }
+    }
+}
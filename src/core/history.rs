@@ -0,0 +1,109 @@
+use super::*;
+
+/// A `Play` along with what it takes to take it back.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct Record {
+    /// The play.
+    pub play:     Play,
+    /// The piece captured by the play, if any.
+    pub capture:  Option<(Player, Piece)>,
+    /// The spare card before the play (swapped into the hand).
+    pub spare:    usize,
+    /// The draw timer before the play.
+    pub timer:    u8,
+    /// The state before the play.
+    pub state:    State,
+    /// The key before the play (see `Game::key`).
+    pub key:      u64,
+    /// The Wind Spirit's square before the play.
+    pub spirit:   Option<Square>,
+    /// The hidden ninjas before the play.
+    pub hidden:   Bitboard,
+    /// `Game::suspects` before the play.
+    pub suspects: Bitboard,
+}
+
+impl Record {
+    pub fn player(&self) -> Player {
+        match self.state {
+            State::Turn(player) => player,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Whether the play moved a ninja that stayed hidden (Light and Shadow):
+    /// the opponent only knows which card was played.
+    pub fn is_hidden(&self) -> bool {
+        match self.play {
+            Play::Card { src, .. } | Play::Wind { src, .. } =>
+                self.hidden.contains(src) && self.capture.is_none(),
+            _ => false,
+        }
+    }
+}
+
+/// The plays of a `Game`: the ones that have been played, and the ones that
+/// have been undone and can be redone.
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct History {
+    past:   Vec<Record>,
+    future: Vec<Play>,
+}
+
+impl History {
+    /// The played records, oldest first.
+    pub fn past(&self) -> &[Record] {
+        &self.past
+    }
+
+    /// The undone plays, next redo last.
+    pub fn future(&self) -> &[Play] {
+        &self.future
+    }
+
+    pub fn plays(&self) -> impl '_ + Iterator<Item = Play> {
+        self.past.iter().map(|record| record.play)
+    }
+
+    pub fn len(&self) -> usize {
+        self.past.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.past.is_empty()
+    }
+
+    pub fn last(&self) -> Option<&Record> {
+        self.past.last()
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.past.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.future.is_empty()
+    }
+}
+
+impl History {
+    pub(super) fn push(&mut self, record: Record) {
+        self.past.push(record);
+    }
+
+    pub(super) fn pop(&mut self) -> Option<Record> {
+        self.past.pop()
+    }
+
+    pub(super) fn push_future(&mut self, play: Play) {
+        self.future.push(play);
+    }
+
+    pub(super) fn pop_future(&mut self) -> Option<Play> {
+        self.future.pop()
+    }
+
+    pub(super) fn clear_future(&mut self) {
+        self.future.clear();
+    }
+}
@@ -1,28 +1,96 @@
+mod bitboard;
 mod board;
+mod builder;
 mod card;
+mod card_file;
+mod deal;
+mod error;
 mod game;
+mod history;
 mod r#move;
+mod notation;
+mod perft;
 mod piece;
 mod player;
+mod position;
+mod random;
+mod record;
+mod rules;
+mod size;
 mod square;
+mod symmetry;
 mod utils;
+mod zobrist;
 
+pub use bitboard::*;
 pub use board::*;
+pub use builder::*;
 pub use card::*;
+pub use deal::*;
+pub use error::*;
 pub use game::*;
+pub use history::*;
+pub use notation::*;
 pub use piece::*;
 pub use player::*;
+pub use position::*;
 pub use r#move::*;
+pub use random::*;
+pub use record::*;
+pub use rules::*;
+pub use size::*;
 pub use square::*;
+use std::borrow::Cow;
+pub use utils::*;
 
 macro_rules! card {
     ($name:literal $stamp:ident [$($move:expr,)*]) => {
-        Card { name: $name, stamp: $stamp, moves: &[ $( Move($move.0, $move.1), )* ] }
+        Card::new($name, $stamp, &[ $( Move($move.0, $move.1), )* ])
+    };
+    ($name:literal $stamp:ident [$($move:expr,)*] [$($spirit:expr,)*]) => {
+        Card::wind(
+            $name,
+            $stamp,
+            &[ $( Move($move.0, $move.1), )* ],
+            &[ $( Move($spirit.0, $spirit.1), )* ],
+        )
     };
 }
 
-pub const SIZE: usize = 5;
+/// The width and height of the largest board (see `Size`).
+pub const MAX_SIZE: usize = 7;
+pub const SQUARES: usize = MAX_SIZE * MAX_SIZE;
 pub const HAND: usize = 2;
+/// The largest number of moves on a card of `CARDS`.
+pub const MAX_MOVES: usize = max_moves(CARDS);
+/// The largest number of plays in a position with `CARDS` (a wind card can
+/// pair each of its moves with each of the Wind Spirit's). `Plays` holds that
+/// many without allocating.
+pub const MAX_PLAYS: usize = HAND * (MAX_SIZE * MAX_MOVES * MAX_MOVES + MAX_MOVES);
+
+const fn max_moves(cards: &[Card]) -> usize {
+    const fn len(moves: &Moves) -> usize {
+        match moves {
+            Cow::Borrowed(moves) => moves.len(),
+            Cow::Owned(_) => 0,
+        }
+    }
+
+    let mut max = 0;
+    let mut i = 0;
+
+    while i < cards.len() {
+        if len(&cards[i].moves) > max {
+            max = len(&cards[i].moves);
+        }
+        if len(&cards[i].spirit) > max {
+            max = len(&cards[i].spirit);
+        }
+        i += 1;
+    }
+
+    max
+}
 pub const CARDS: &[Card] = &[
     // ======= //
     // Neutral //
@@ -117,4 +185,191 @@ pub const CARDS: &[Card] = &[
         (Up(0), Left(1)),
         (Down(1), Right(1)),
     ]),
+    // ============= //
+    // Sensei's Path //
+    // ============= //
+    card!("Bear" Blue [
+        (Up(1), Left(1)),
+        (Up(1), Right(0)),
+        (Down(1), Right(1)),
+    ]),
+    card!("Dog" Blue [
+        (Up(1), Left(1)),
+        (Up(0), Left(1)),
+        (Down(1), Left(1)),
+    ]),
+    card!("Fox" Red [
+        (Up(1), Right(1)),
+        (Up(0), Right(1)),
+        (Down(1), Right(1)),
+    ]),
+    card!("Giraffe" Blue [
+        (Up(1), Left(2)),
+        (Up(1), Right(2)),
+        (Down(1), Right(0)),
+    ]),
+    card!("Iguana" Red [
+        (Up(1), Left(2)),
+        (Up(1), Right(0)),
+        (Down(1), Right(1)),
+    ]),
+    card!("Kirin" Red [
+        (Up(2), Left(1)),
+        (Up(2), Right(1)),
+        (Down(2), Right(0)),
+    ]),
+    card!("Mouse" Blue [
+        (Up(1), Right(0)),
+        (Up(0), Right(1)),
+        (Down(1), Left(1)),
+    ]),
+    card!("Otter" Red [
+        (Up(1), Left(1)),
+        (Up(0), Right(2)),
+        (Down(1), Right(1)),
+    ]),
+    card!("Panda" Red [
+        (Up(1), Right(1)),
+        (Up(1), Right(0)),
+        (Down(1), Left(1)),
+    ]),
+    card!("Phoenix" Blue [
+        (Up(1), Left(1)),
+        (Up(1), Right(1)),
+        (Up(0), Left(2)),
+        (Up(0), Right(2)),
+    ]),
+    card!("Rat" Red [
+        (Up(1), Right(0)),
+        (Up(0), Left(1)),
+        (Down(1), Right(1)),
+    ]),
+    card!("Sable" Blue [
+        (Up(1), Right(1)),
+        (Up(0), Left(2)),
+        (Down(1), Left(1)),
+    ]),
+    card!("Sea Snake" Blue [
+        (Up(1), Right(0)),
+        (Up(0), Right(2)),
+        (Down(1), Left(1)),
+    ]),
+    card!("Tanuki" Blue [
+        (Up(1), Right(0)),
+        (Up(1), Right(2)),
+        (Down(1), Left(1)),
+    ]),
+    card!("Turtle" Red [
+        (Up(0), Left(2)),
+        (Up(0), Right(2)),
+        (Down(1), Left(1)),
+        (Down(1), Right(1)),
+    ]),
+    card!("Viper" Red [
+        (Up(1), Right(0)),
+        (Up(0), Left(2)),
+        (Down(1), Right(1)),
+    ]),
+    // ================ //
+    // Way of the Wind //
+    // ================ //
+    card!("Bat" Red [
+        (Up(1), Left(1)),
+        (Up(1), Right(1)),
+    ] [
+        (Down(1), Right(0)),
+        (Up(0), Left(2)),
+        (Up(0), Right(2)),
+    ]),
+    card!("Eagle" Blue [
+        (Up(2), Right(0)),
+    ] [
+        (Up(1), Left(1)),
+        (Up(1), Right(1)),
+        (Down(1), Left(1)),
+        (Down(1), Right(1)),
+    ]),
+    card!("Lizard" Red [
+        (Up(1), Left(2)),
+        (Down(1), Right(1)),
+    ] [
+        (Up(1), Right(0)),
+        (Up(0), Left(1)),
+    ]),
+    card!("Zebra" Blue [
+        (Up(1), Right(2)),
+        (Down(1), Left(1)),
+    ] [
+        (Up(1), Right(0)),
+        (Up(0), Right(1)),
+    ]),
+    card!("Mole" Blue [
+        (Up(1), Right(0)),
+    ] [
+        (Up(0), Left(1)),
+        (Up(0), Right(1)),
+        (Down(1), Right(0)),
+    ]),
+    card!("Plover" Red [
+        (Up(1), Left(1)),
+        (Down(1), Right(1)),
+    ] [
+        (Up(2), Right(0)),
+        (Down(2), Right(0)),
+    ]),
+    card!("Octopus" Blue [
+        (Up(1), Right(1)),
+        (Down(1), Left(1)),
+    ] [
+        (Up(2), Right(0)),
+        (Down(2), Right(0)),
+    ]),
+    card!("Centipede" Red [
+        (Up(0), Left(1)),
+        (Up(0), Right(1)),
+    ] [
+        (Up(1), Left(1)),
+        (Up(1), Right(1)),
+    ]),
+    // ========== //
+    // Long range //
+    // ========== //
+    card!("Falcon" Red [
+        (Up(3), Right(0)),
+        (Down(1), Left(1)),
+        (Down(1), Right(1)),
+    ]),
+    card!("Stag" Blue [
+        (Up(2), Left(1)),
+        (Up(2), Right(1)),
+        (Down(1), Right(0)),
+    ]),
+    card!("Wolf" Red [
+        (Up(1), Left(3)),
+        (Up(0), Left(1)),
+        (Down(1), Right(1)),
+    ]),
+    card!("Jackal" Blue [
+        (Up(1), Right(3)),
+        (Up(0), Right(1)),
+        (Down(1), Left(1)),
+    ]),
+    card!("Hawk" Red [
+        (Up(2), Left(2)),
+        (Down(1), Right(1)),
+    ]),
+    card!("Kite" Blue [
+        (Up(2), Right(2)),
+        (Down(1), Left(1)),
+    ]),
+    card!("Lion" Blue [
+        (Up(1), Right(0)),
+        (Up(0), Left(3)),
+        (Up(0), Right(3)),
+    ]),
+    card!("Leopard" Red [
+        (Up(1), Left(1)),
+        (Up(1), Right(1)),
+        (Down(3), Right(0)),
+    ]),
 ];
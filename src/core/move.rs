@@ -1,4 +1,5 @@
 use super::*;
+use std::borrow::Cow;
 
 #[doc(hidden)]
 macro_rules! declare {
@@ -27,7 +28,8 @@ declare!(
     Horizontal { Left Right }
 );
 
-pub type Moves = &'static [Move];
+/// The moves of a card: `'static` for `CARDS`, owned for loaded cards.
+pub type Moves = Cow<'static, [Move]>;
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub struct Move(pub Vertical, pub Horizontal);
@@ -41,6 +43,44 @@ impl Move {
         self.1
     }
 
+    /// The move of `(file, rank)` offset, from Red's point of view, if within
+    /// `±MAX_SIZE / 2` (±3).
+    pub fn from_offset(file: isize, rank: isize) -> Option<Self> {
+        let reach = (MAX_SIZE / 2) as isize;
+        let range = -reach..=reach;
+
+        if !range.contains(&file) || !range.contains(&rank) {
+            None
+        } else {
+            Some(Self(
+                if rank < 0 {
+                    Down(-rank as usize)
+                } else {
+                    Up(rank as usize)
+                },
+                if file < 0 {
+                    Left(-file as usize)
+                } else {
+                    Right(file as usize)
+                },
+            ))
+        }
+    }
+
+    /// The `(file, rank)` offset of this move, from Red's point of view.
+    pub const fn offset(&self) -> (isize, isize) {
+        let file = match self.1 {
+            Left(u) => -(u as isize),
+            Right(u) => u as isize,
+        };
+        let rank = match self.0 {
+            Down(u) => -(u as isize),
+            Up(u) => u as isize,
+        };
+
+        (file, rank)
+    }
+
     pub fn flip(&mut self) {
         self.0.flip();
         self.1.flip();
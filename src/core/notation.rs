@@ -0,0 +1,287 @@
+//! Text form of plays.
+//!
+//! A play names its card, since `Play` only holds an index into the hand of
+//! the player to move:
+//!
+//! - `Tiger c1-c3` moves the piece on `c1` to `c3` with Tiger,
+//! - `Tiger c3-d4` moves the Wind Spirit if it stands on `c3`,
+//! - `Ox c1-c2 c3-c4` moves a piece then the Wind Spirit with a wind card,
+//! - `discard Crab` discards Crab when no card can be played.
+//!
+//! `Notation` is the context-free form, `Game::notation` and `Game::parse_play`
+//! convert from and to `Play` for the player to move.
+
+use super::*;
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::str::FromStr;
+
+/// A `Play` in text form.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum Notation {
+    /// A card moving a piece or the Wind Spirit, then the Wind Spirit for
+    /// wind cards.
+    Move {
+        card:   String,
+        src:    Square,
+        dest:   Square,
+        spirit: Option<(Square, Square)>,
+    },
+    /// A discarded card.
+    Discard(String),
+}
+
+impl Notation {
+    pub fn card(&self) -> &str {
+        match self {
+            Self::Move { card, .. } => card,
+            Self::Discard(card) => card,
+        }
+    }
+}
+
+impl Display for Notation {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Move {
+                card,
+                src,
+                dest,
+                spirit,
+            } => {
+                write!(f, "{} {}-{}", card, src, dest)?;
+                if let Some((src, dest)) = spirit {
+                    write!(f, " {}-{}", src, dest)?;
+                }
+                Ok(())
+            }
+            Self::Discard(card) => write!(f, "discard {}", card),
+        }
+    }
+}
+
+impl FromStr for Notation {
+    type Err = NotationError;
+
+    fn from_str(str: &str) -> Result<Self, NotationError> {
+        fn name<'a>(words: impl Iterator<Item = &'a str>) -> Result<String, NotationError> {
+            let name = words.collect::<Vec<_>>().join(" ");
+
+            if name.is_empty() {
+                Err(NotationError::Syntax)
+            } else {
+                Ok(name)
+            }
+        }
+
+        fn squares(word: &str) -> Option<(Square, Square)> {
+            let (src, dest) = word.split_once('-')?;
+            Some((src.parse().ok()?, dest.parse().ok()?))
+        }
+
+        let mut words = str.split_whitespace().collect::<Vec<_>>();
+
+        if words.first() == Some(&"discard") {
+            return Ok(Self::Discard(name(words.drain(1..))?));
+        }
+
+        let mut moves = Vec::new();
+        while let Some(squares) = words.last().and_then(|word| squares(word)) {
+            moves.insert(0, squares);
+            words.pop();
+        }
+
+        match moves[..] {
+            [(src, dest)] => Ok(Self::Move {
+                card: name(words.into_iter())?,
+                src,
+                dest,
+                spirit: None,
+            }),
+            [(src, dest), spirit] => Ok(Self::Move {
+                card: name(words.into_iter())?,
+                src,
+                dest,
+                spirit: Some(spirit),
+            }),
+            _ => Err(NotationError::Syntax),
+        }
+    }
+}
+
+impl Game {
+    /// The notation of `play` for the player to move.
+    ///
+    /// Panics if the game is over.
+    pub fn notation(&self, play: Play) -> Notation {
+        let player = self.player().expect("game is over");
+        let card = self.card(player, play.card()).name.to_string();
+
+        match play {
+            Play::Card { src, dest, .. } => Notation::Move {
+                card,
+                src,
+                dest,
+                spirit: None,
+            },
+            Play::Spirit { dest, .. } => Notation::Move {
+                card,
+                src: self.spirit().expect("no Wind Spirit"),
+                dest,
+                spirit: None,
+            },
+            Play::Wind {
+                src, dest, spirit, ..
+            } => Notation::Move {
+                card,
+                src,
+                dest,
+                spirit: Some((self.spirit().expect("no Wind Spirit"), spirit)),
+            },
+            Play::Discard(_) => Notation::Discard(card),
+        }
+    }
+
+    /// The `Play` of `notation` for the player to move.
+    ///
+    /// Card names are case insensitive. The play is not checked (see
+    /// `Game::check`).
+    pub fn play_of(&self, notation: &Notation) -> Result<Play, NotationError> {
+        let player = self.player().ok_or(NotationError::Over)?;
+        let card = (0..HAND)
+            .find(|&i| {
+                self.card(player, i)
+                    .name
+                    .eq_ignore_ascii_case(notation.card())
+            })
+            .ok_or_else(|| NotationError::Card(notation.card().to_string()))?;
+
+        match *notation {
+            Notation::Discard(_) => Ok(Play::Discard(card)),
+            Notation::Move {
+                src,
+                dest,
+                spirit: None,
+                ..
+            } =>
+                if Some(src) == self.spirit() {
+                    Ok(Play::Spirit { card, dest })
+                } else {
+                    Ok(Play::Card { card, src, dest })
+                },
+            Notation::Move {
+                src,
+                dest,
+                spirit: Some((from, spirit)),
+                ..
+            } =>
+                if Some(from) == self.spirit() {
+                    Ok(Play::Wind {
+                        card,
+                        src,
+                        dest,
+                        spirit,
+                    })
+                } else {
+                    Err(NotationError::Spirit(from))
+                },
+        }
+    }
+
+    /// Parses a `Notation` into a `Play` for the player to move.
+    pub fn parse_play(&self, str: &str) -> Result<Play, NotationError> {
+        self.play_of(&str.parse()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let rules = Rules {
+            wind: true,
+            ..Rules::default()
+        };
+        let mut rng = Rng::new(Seed(1));
+
+        for _ in 0..10 {
+            let sets = [CardSet::Base, CardSet::WayOfTheWind];
+            let deal = Deal::random_from_sets(Seed(rng.next_u64()), &sets).unwrap();
+            let mut game = Game::from_deal(deal, rules);
+
+            while game.player().is_some() {
+                let plays = game.legal_plays();
+                for &play in &plays {
+                    let notation = game.notation(play);
+                    assert_eq!(notation.to_string().parse(), Ok(notation.clone()));
+                    assert_eq!(game.play_of(&notation), Ok(play));
+                }
+                game.play(plays[rng.below(plays.len())]);
+            }
+        }
+    }
+
+    #[test]
+    fn syntax() {
+        let parse = |str: &str| str.parse::<Notation>();
+
+        assert_eq!(
+            parse("Tiger c1-c3 c3-c4"),
+            Ok(Notation::Move {
+                card:   String::from("Tiger"),
+                src:    Square(C, One),
+                dest:   Square(C, Three),
+                spirit: Some((Square(C, Three), Square(C, Four))),
+            })
+        );
+        assert_eq!(
+            parse("discard Tiger"),
+            Ok(Notation::Discard(String::from("Tiger")))
+        );
+        for str in [
+            "",
+            "Tiger",
+            "discard",
+            "c1-c3",
+            "Tiger c1",
+            "Tiger c1-z9",
+            "Tiger c1-c3 c3-c4 c4-c5",
+        ] {
+            assert_eq!(parse(str), Err(NotationError::Syntax), "{:?}", str);
+        }
+    }
+
+    #[test]
+    fn plays() {
+        let cards = Card::builtin();
+        let deal = Deal::parse("Frog,Dog,Tiger,Crab,Monkey", &cards).unwrap();
+        let mut game = Game::with_cards(cards, deal, Rules::default());
+
+        // Blue moves first, with Tiger and Crab
+        assert_eq!(
+            game.parse_play("tiger c5-c3"),
+            Ok(Play::Card {
+                card: 0,
+                src:  Square(C, Five),
+                dest: Square(C, Three),
+            })
+        );
+        assert_eq!(
+            game.parse_play("Frog c1-c2"),
+            Err(NotationError::Card(String::from("Frog")))
+        );
+        assert_eq!(
+            game.parse_play("Tiger c5-c3 c3-c4"),
+            Err(NotationError::Spirit(Square(C, Three)))
+        );
+
+        while game.player().is_some() {
+            let plays = game.legal_plays();
+            game.play(plays[0]);
+        }
+        assert_eq!(game.parse_play("Tiger c5-c3"), Err(NotationError::Over));
+    }
+}
@@ -0,0 +1,122 @@
+//! Move generation checks.
+//!
+//! `perft` counts the positions reachable in exactly `depth` plies. Finished
+//! games are not expanded, so they only count at depth 0.
+
+use super::*;
+
+impl Game {
+    /// The number of leaves of the game tree to `depth` plies.
+    pub fn perft(&self, depth: usize) -> u64 {
+        perft(&mut self.clone(), depth)
+    }
+
+    /// `perft` broken down by root play.
+    pub fn divide(&self, depth: usize) -> Vec<(Play, u64)> {
+        if depth == 0 || self.player().is_none() {
+            return Vec::new();
+        }
+
+        let mut game = self.clone();
+        self.legal_plays()
+            .iter()
+            .map(|&play| {
+                game.play(play);
+                let nodes = perft(&mut game, depth - 1);
+                game.undo();
+                (play, nodes)
+            })
+            .collect()
+    }
+}
+
+fn perft(game: &mut Game, depth: usize) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+    if game.player().is_none() {
+        return 0;
+    }
+
+    let plays = game.legal_plays();
+    if depth == 1 {
+        return plays.len() as u64;
+    }
+
+    let mut nodes = 0;
+    for &play in &plays {
+        game.play(play);
+        nodes += perft(game, depth - 1);
+        game.undo();
+    }
+    nodes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Known-good leaf counts of deals (with the Wind Spirit or not), by depth
+    /// from 1.
+    const PERFTS: &[(&str, bool, &[u64])] = &[
+        ("Tiger,Crab,Monkey,Crane,Dragon", false, &[
+            10, 130, 1677, 19163, 262295,
+        ]),
+        ("Frog,Goose,Horse,Eel,Rabbit", false, &[
+            9, 72, 704, 8884, 114121,
+        ]),
+        ("Elephant,Mantis,Boar,Ox,Cobra", false, &[
+            16, 160, 2380, 38724, 574869,
+        ]),
+        ("Kirin,Phoenix,Sea Snake,Turtle,Viper", false, &[
+            16, 80, 1255, 13302, 195061,
+        ]),
+        ("Tiger,Crab,Monkey,Crane,Dragon", true, &[
+            13, 249, 4073, 62357,
+        ]),
+        ("Bat,Eagle,Lizard,Zebra,Mole", true, &[12, 382, 5554, 98052]),
+        ("Tiger,Plover,Crab,Octopus,Centipede", true, &[
+            6, 58, 457, 4773, 60100,
+        ]),
+    ];
+
+    #[test]
+    fn deals() {
+        for &(deal, wind, counts) in PERFTS {
+            let deal = Deal::parse(deal, CARDS).unwrap();
+            let rules = Rules {
+                wind,
+                ..Rules::default()
+            };
+            let game = Game::with_cards(Card::builtin(), deal, rules);
+
+            for (depth, &count) in (1..).zip(counts) {
+                assert_eq!(game.perft(depth), count, "{:?} at depth {}", deal, depth);
+            }
+        }
+    }
+
+    #[test]
+    fn kings() {
+        let position = "k4/5/5/5/4K r:Tiger,Crab b:Monkey,Crane s:Dragon r 40";
+        let game = Game::from_position(position.parse().unwrap(), Rules::default()).unwrap();
+
+        for (depth, count) in (1..).zip([3, 6, 22, 99, 480]) {
+            assert_eq!(game.perft(depth), count, "at depth {}", depth);
+        }
+    }
+
+    #[test]
+    fn divide() {
+        let deal = Deal::parse("Tiger,Crab,Monkey,Crane,Dragon", CARDS).unwrap();
+        let game = Game::with_cards(Card::builtin(), deal, Rules::default());
+        let divide = game.divide(3);
+
+        assert_eq!(divide.len(), 10);
+        assert_eq!(divide.iter().map(|(_, count)| count).sum::<u64>(), 1677);
+        assert_eq!(
+            game,
+            Game::with_cards(Card::builtin(), deal, Rules::default())
+        );
+    }
+}
@@ -1,40 +1,39 @@
 use super::*;
 
 pub use Piece::*;
+/// Pawns are named after their home file.
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub enum Piece {
     PawnA,
     PawnB,
-    King,
+    PawnC,
     PawnD,
     PawnE,
+    PawnF,
+    PawnG,
+    King,
 }
 
 impl Piece {
+    /// Pawns by home file, then the king (see `Size::pieces` for those of a
+    /// board).
+    pub const ALL: [Self; MAX_SIZE + 1] = [PawnA, PawnB, PawnC, PawnD, PawnE, PawnF, PawnG, King];
+
     pub fn index(&self) -> usize {
         *self as usize
     }
 
-    pub fn file(&self) -> File {
-        match *self {
-            PawnA => A,
-            PawnB => B,
-            King => C,
-            PawnD => D,
-            PawnE => E,
+    /// The home file of this piece on a board of `size`.
+    pub fn file(&self, size: Size) -> File {
+        match self {
+            King => File::all()[size.get() / 2],
+            pawn => File::all()[pawn.index()],
         }
     }
 }
 
 impl From<usize> for Piece {
     fn from(index: usize) -> Self {
-        match index {
-            0 => PawnA,
-            1 => PawnB,
-            2 => King,
-            3 => PawnD,
-            4 => PawnE,
-            _ => panic!(),
-        }
+        Self::ALL[index]
     }
 }
@@ -1,19 +1,17 @@
 use super::*;
 use std::ops::Not;
+use std::str::FromStr;
 
 pub use Player::*;
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub enum Player {
-    Red,  // The "White" side (Rank::One)
-    Blue, // The "Black" side (Rank::Five) (flipped)
+    Red,  // The "White" side (first rank)
+    Blue, // The "Black" side (last rank) (flipped)
 }
 
 impl Player {
-    pub fn rank(&self) -> Rank {
-        match *self {
-            Red => One,
-            Blue => Five,
-        }
+    pub fn index(&self) -> usize {
+        *self as usize
     }
 
     pub fn invert(&mut self) {
@@ -44,3 +42,15 @@ impl Not for Player {
         self
     }
 }
+
+impl FromStr for Player {
+    type Err = ();
+
+    fn from_str(str: &str) -> Result<Self, ()> {
+        match str {
+            "red" => Ok(Red),
+            "blue" => Ok(Blue),
+            _ => Err(()),
+        }
+    }
+}
@@ -0,0 +1,311 @@
+//! Position strings.
+//!
+//! A position is written on one line, e.g. for the starting position of a
+//! game:
+//!
+//! ```text
+//! ppkpp/5/5/5/PPKPP r:Frog,Goose b:Horse,Eel s:Rabbit r 40
+//! ```
+//!
+//! - the board, from Blue's home rank down to Red's, ranks separated by `/`
+//!   (five ranks of five squares, or seven of seven): `K`, `P` and `N` (a
+//!   hidden ninja) for Red, `k`, `p` and `n` for Blue, `w` for the Wind Spirit
+//!   and digits for runs of empty squares,
+//! - each side's cards (`r:` and `b:`) and the spare card (`s:`), spaces in
+//!   names written as `_`,
+//! - the state: `r` or `b` for the player to move, `red-won`, `blue-won`,
+//!   `draw-timer` or `draw-repetition`,
+//! - the draw timer.
+
+use super::*;
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::str::FromStr;
+
+/// A full game position, without history.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Position {
+    pub cards:  Cards,
+    pub size:   Size,
+    pub board:  Board,
+    /// The hands and spare card.
+    pub deal:   Deal,
+    pub state:  State,
+    pub timer:  u8,
+    /// The Wind Spirit's square (Way of the Wind).
+    pub spirit: Option<Square>,
+    /// The squares of the hidden ninjas (Light and Shadow).
+    pub hidden: Bitboard,
+}
+
+impl Position {
+    /// Parses a position whose card names are in `cards`.
+    pub fn parse(str: &str, cards: &Cards) -> Result<Self, PositionError> {
+        let fields = str.split_whitespace().collect::<Vec<_>>();
+        let [board, red, blue, spare, state, timer] = fields[..] else {
+            return Err(PositionError::Syntax);
+        };
+
+        let card = |name: &str| {
+            let name = name.replace('_', " ");
+            cards
+                .iter()
+                .position(|card| card.name.eq_ignore_ascii_case(&name))
+                .ok_or(PositionError::Card(name))
+        };
+        let hand = |field: &str, prefix: &str| {
+            let names = field
+                .strip_prefix(prefix)
+                .ok_or(PositionError::Syntax)?
+                .split(',')
+                .map(card)
+                .collect::<Result<Vec<_>, _>>()?;
+            <[usize; HAND]>::try_from(names).map_err(|_| PositionError::Syntax)
+        };
+
+        let (size, board, spirit, hidden) = parse_board(board)?;
+
+        Ok(Self {
+            cards: cards.clone(),
+            size,
+            board,
+            deal: Deal {
+                red:   hand(red, "r:")?,
+                blue:  hand(blue, "b:")?,
+                spare: card(spare.strip_prefix("s:").ok_or(PositionError::Syntax)?)?,
+            },
+            state: match state {
+                "r" => State::Turn(Red),
+                "b" => State::Turn(Blue),
+                "red-won" => State::Won(Red),
+                "blue-won" => State::Won(Blue),
+                "draw-timer" => State::Draw(DrawRule::Timer),
+                "draw-repetition" => State::Draw(DrawRule::Repetition),
+                _ => return Err(PositionError::Syntax),
+            },
+            timer: timer.parse().map_err(|_| PositionError::Syntax)?,
+            spirit,
+            hidden,
+        })
+    }
+}
+
+fn parse_board(str: &str) -> Result<(Size, Board, Option<Square>, Bitboard), PositionError> {
+    let mut board = Board::EMPTY;
+    let mut spirit = None;
+    let mut hidden = Bitboard::EMPTY;
+    // Pawns are numbered in reading order
+    let mut pawns = [0; 2];
+    let ranks = str.split('/').collect::<Vec<_>>();
+    let size = Size::try_from(ranks.len()).map_err(|_| PositionError::Board)?;
+
+    for (rank, row) in size.ranks().rev().zip(ranks) {
+        let mut files = size.files();
+
+        for char in row.chars() {
+            if let Some(empty) = char.to_digit(10) {
+                for _ in 0..empty {
+                    files.next().ok_or(PositionError::Board)?;
+                }
+                continue;
+            }
+
+            let square = Square(files.next().ok_or(PositionError::Board)?, rank);
+            let player = if char.is_ascii_uppercase() { Red } else { Blue };
+
+            let mut pawn = || {
+                let pawn = size
+                    .pieces()
+                    .filter(|&piece| piece != King)
+                    .nth(pawns[player.index()])
+                    .ok_or(PositionError::Pieces(player))?;
+                pawns[player.index()] += 1;
+                Ok(pawn)
+            };
+
+            match char.to_ascii_lowercase() {
+                'k' => board[square] = Some((player, King)),
+                'p' => board[square] = Some((player, pawn()?)),
+                'n' => {
+                    board[square] = Some((player, pawn()?));
+                    hidden |= Bitboard::from(square);
+                }
+                'w' if spirit.is_none() && char == 'w' => spirit = Some(square),
+                _ => return Err(PositionError::Board),
+            }
+        }
+
+        if files.next().is_some() {
+            return Err(PositionError::Board);
+        }
+    }
+
+    Ok((size, board, spirit, hidden))
+}
+
+impl FromStr for Position {
+    type Err = PositionError;
+
+    /// Parses a position whose card names are in `CARDS`.
+    fn from_str(str: &str) -> Result<Self, PositionError> {
+        Self::parse(str, &Card::builtin())
+    }
+}
+
+impl Display for Position {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        for (i, rank) in self.size.ranks().rev().enumerate() {
+            if i != 0 {
+                write!(f, "/")?;
+            }
+
+            let mut empty = 0;
+            for file in self.size.files() {
+                let square = Square(file, rank);
+                let char = match self.board[square] {
+                    Some((player, piece)) => {
+                        let char = if piece == King {
+                            'k'
+                        } else if self.hidden.contains(square) {
+                            'n'
+                        } else {
+                            'p'
+                        };
+
+                        if player == Red {
+                            char.to_ascii_uppercase()
+                        } else {
+                            char
+                        }
+                    }
+                    None if self.spirit == Some(square) => 'w',
+                    None => {
+                        empty += 1;
+                        continue;
+                    }
+                };
+
+                if empty != 0 {
+                    write!(f, "{}", empty)?;
+                    empty = 0;
+                }
+                write!(f, "{}", char)?;
+            }
+            if empty != 0 {
+                write!(f, "{}", empty)?;
+            }
+        }
+
+        let name = |card: usize| self.cards[card].name.replace(' ', "_");
+        let Deal { red, blue, spare } = self.deal;
+
+        write!(f, " r:{},{}", name(red[0]), name(red[1]))?;
+        write!(f, " b:{},{}", name(blue[0]), name(blue[1]))?;
+        write!(f, " s:{}", name(spare))?;
+
+        match self.state {
+            State::Turn(Red) => write!(f, " r")?,
+            State::Turn(Blue) => write!(f, " b")?,
+            State::Won(Red) => write!(f, " red-won")?,
+            State::Won(Blue) => write!(f, " blue-won")?,
+            State::Draw(DrawRule::Timer) => write!(f, " draw-timer")?,
+            State::Draw(DrawRule::Repetition) => write!(f, " draw-repetition")?,
+        }
+
+        write!(f, " {}", self.timer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parses back every position of random games dealt from `sets`.
+    fn round_trip(sets: &[CardSet], rules: Rules) {
+        let mut rng = Rng::new(Seed(1));
+
+        for _ in 0..10 {
+            let deal = Deal::random_from_sets(Seed(rng.next_u64()), sets).unwrap();
+            let mut game = Game::from_deal(deal, rules);
+
+            loop {
+                let position = game.position();
+                let text = position.to_string();
+                let parsed = text.parse::<Position>().unwrap();
+
+                assert_eq!(parsed.to_string(), text);
+                assert_eq!(parsed.size, position.size);
+                assert_eq!(parsed.deal, position.deal);
+                assert_eq!(parsed.state, position.state);
+                assert_eq!(parsed.timer, position.timer);
+                assert_eq!(parsed.spirit, position.spirit);
+                assert_eq!(parsed.hidden, position.hidden);
+
+                if game.player().is_none() {
+                    break;
+                }
+                let plays = game.legal_plays();
+                game.play(plays[rng.below(plays.len())]);
+            }
+        }
+    }
+
+    #[test]
+    fn base() {
+        round_trip(&[CardSet::Base], Rules::default());
+    }
+
+    #[test]
+    fn wind() {
+        let rules = Rules {
+            wind: true,
+            ..Rules::default()
+        };
+        round_trip(&[CardSet::Base, CardSet::WayOfTheWind], rules);
+    }
+
+    #[test]
+    fn shadow() {
+        let rules = Rules {
+            shadow: Some(Blue),
+            ..Rules::default()
+        };
+        round_trip(&[CardSet::Base], rules);
+    }
+
+    #[test]
+    fn seven() {
+        let rules = Rules {
+            size: Size::SEVEN,
+            ..Rules::default()
+        };
+        round_trip(&[CardSet::Base, CardSet::LongRange], rules);
+    }
+
+    #[test]
+    fn errors() {
+        let parse = |str: &str| str.parse::<Position>().unwrap_err();
+
+        assert_eq!(
+            parse("ppkpp/5/5/5/PPKPP r:Frog,Goose b:Horse,Eel s:Rabbit r"),
+            PositionError::Syntax
+        );
+        assert_eq!(
+            parse("ppkpp/5/5/5/PPKPP r:Frog,Goose b:Horse,Eel s:Rabbit x 40"),
+            PositionError::Syntax
+        );
+        assert_eq!(
+            parse("ppkpp/5/5/PPKPP r:Frog,Goose b:Horse,Eel s:Rabbit r 40"),
+            PositionError::Board
+        );
+        assert_eq!(
+            parse("ppkpp/6/5/5/PPKPP r:Frog,Goose b:Horse,Eel s:Rabbit r 40"),
+            PositionError::Board
+        );
+        assert_eq!(
+            parse("ppkpp/5/5/5/PPKPP r:Frog,Goose b:Horse,Eel s:Unicorn r 40"),
+            PositionError::Card(String::from("Unicorn"))
+        );
+    }
+}
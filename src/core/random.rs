@@ -0,0 +1,75 @@
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::num::ParseIntError;
+use std::str::FromStr;
+use std::time::SystemTime;
+
+/// A printable random seed, written as 16 hexadecimal digits.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Seed(pub u64);
+
+impl Seed {
+    /// A seed from the clock and the process id.
+    pub fn random() -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_nanos() as u64);
+
+        Self(Rng::mix(nanos ^ ((std::process::id() as u64) << 32)))
+    }
+}
+
+impl Display for Seed {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+impl FromStr for Seed {
+    type Err = ParseIntError;
+
+    fn from_str(str: &str) -> Result<Self, ParseIntError> {
+        let str = str.strip_prefix("0x").unwrap_or(str);
+        u64::from_str_radix(str, 16).map(Self)
+    }
+}
+
+/// A SplitMix64 pseudo-random number generator.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct Rng(u64);
+
+impl Rng {
+    const GAMMA: u64 = 0x9E3779B97F4A7C15;
+
+    pub fn new(seed: Seed) -> Self {
+        Self(seed.0)
+    }
+
+    /// SplitMix64's output function.
+    pub const fn mix(x: u64) -> u64 {
+        let mut z = x.wrapping_add(Self::GAMMA);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let x = self.0;
+        self.0 = self.0.wrapping_add(Self::GAMMA);
+        Self::mix(x)
+    }
+
+    /// A number in `0..n`.
+    pub fn below(&mut self, n: usize) -> usize {
+        debug_assert!(n > 0);
+
+        ((self.next_u64() as u128 * n as u128) >> 64) as usize
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
@@ -0,0 +1,298 @@
+//! Game records.
+//!
+//! A record is a header of `[Tag "value"]` lines followed by one play per
+//! line in move notation, numbered by full move, then the result:
+//!
+//! ```text
+//! [Red "Human"]
+//! [Blue "Human"]
+//! [Date "2021.11.02"]
+//! [Deal "Frog, Goose, Horse, Eel, Rabbit"]
+//! [Result "blue-won"]
+//!
+//! 1. Frog b1-a2
+//! 1... Horse c5-c4
+//! 2. Goose a2-a3
+//! blue-won
+//! ```
+//!
+//! The deal lists Red's cards, Blue's, then the spare. Optional tags are
+//! `Variant` (`wind`, `shadow-red`, `shadow-blue`, `7x7`, separated by spaces)
+//! and `Position`, when the game did not start from the home ranks (see
+//! `Position`). Results are written as in positions, `*` for unfinished games.
+
+use super::*;
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::path::Path;
+use std::time::SystemTime;
+
+/// A game with its players and date.
+#[derive(Clone, Debug)]
+pub struct GameRecord {
+    pub red:  String,
+    pub blue: String,
+    /// `YYYY.MM.DD`.
+    pub date: String,
+    pub game: Game,
+}
+
+impl GameRecord {
+    /// A record of `game` between two humans, dated today.
+    pub fn new(game: Game) -> Self {
+        Self {
+            red: "Human".into(),
+            blue: "Human".into(),
+            date: today(),
+            game,
+        }
+    }
+
+    /// Writes this record to `path`.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), RecordError> {
+        std::fs::write(path, self.to_string()).map_err(|error| RecordError::Io(error.to_string()))
+    }
+
+    /// Loads and replays the record at `path`, whose card names are in
+    /// `cards`.
+    pub fn load(path: impl AsRef<Path>, cards: &Cards) -> Result<Self, RecordError> {
+        let str =
+            std::fs::read_to_string(path).map_err(|error| RecordError::Io(error.to_string()))?;
+        Self::parse(&str, cards)
+    }
+
+    /// Parses and replays a record whose card names are in `cards`.
+    pub fn parse(str: &str, cards: &Cards) -> Result<Self, RecordError> {
+        let invalid = |line, kind| RecordError::Invalid { line, kind };
+        let mut lines = str
+            .lines()
+            .enumerate()
+            .map(|(line, text)| (line + 1, text.trim()))
+            .filter(|(_, text)| !text.is_empty())
+            .peekable();
+
+        let mut red = String::new();
+        let mut blue = String::new();
+        let mut date = String::new();
+        let mut deal = Err(invalid(1, RecordErrorKind::Deal));
+        let mut rules = Rules::default();
+        let mut position = None;
+        let mut result = None;
+
+        while let Some(&(line, text)) = lines.peek() {
+            let Some(tag) = text.strip_prefix('[') else {
+                break;
+            };
+            lines.next();
+
+            let (name, value) = tag
+                .strip_suffix("\"]")
+                .and_then(|tag| tag.split_once(" \""))
+                .ok_or(invalid(line, RecordErrorKind::Tag))?;
+
+            match name {
+                "Red" => red = value.into(),
+                "Blue" => blue = value.into(),
+                "Date" => date = value.into(),
+                "Deal" =>
+                    deal = Deal::parse(value, cards).ok_or(invalid(line, RecordErrorKind::Deal)),
+                "Variant" =>
+                    rules = value
+                        .parse()
+                        .map_err(|()| invalid(line, RecordErrorKind::Variant))?,
+                "Position" =>
+                    position = Some(
+                        Position::parse(value, cards)
+                            .map_err(|error| invalid(line, RecordErrorKind::Position(error)))?,
+                    ),
+                "Result" => result = Some((line, value)),
+                _ => {}
+            }
+        }
+
+        let mut game = match position {
+            Some(position) => Game::from_position(position, rules)
+                .map_err(|error| invalid(1, RecordErrorKind::Position(error)))?,
+            None => Game::with_cards(cards.clone(), deal?, rules),
+        };
+
+        for (line, text) in lines {
+            if RESULTS.contains(&text) {
+                if text != self::result(game.state()) {
+                    return Err(invalid(line, RecordErrorKind::Result));
+                }
+                continue;
+            }
+
+            let notation = text
+                .split_once(". ")
+                .filter(|(number, _)| {
+                    let number = number.strip_suffix("..").unwrap_or(number);
+                    !number.is_empty() && number.bytes().all(|byte| byte.is_ascii_digit())
+                })
+                .map_or(text, |(_, notation)| notation);
+            let play = game
+                .parse_play(notation)
+                .map_err(|error| invalid(line, RecordErrorKind::Notation(error)))?;
+            game.try_play(play)
+                .map_err(|error| invalid(line, RecordErrorKind::Play(error)))?;
+        }
+
+        if let Some((line, result)) = result {
+            if result != self::result(game.state()) {
+                return Err(invalid(line, RecordErrorKind::Result));
+            }
+        }
+
+        Ok(Self {
+            red,
+            blue,
+            date,
+            game,
+        })
+    }
+}
+
+impl Display for GameRecord {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let mut game = self.game.clone();
+        while game.undo().is_some() {}
+
+        let position = game.position();
+        let cards = game.card_set();
+        let deal = position.deal;
+        let rules = game.rules();
+        let result = result(self.game.state());
+
+        writeln!(f, "[Red \"{}\"]", self.red)?;
+        writeln!(f, "[Blue \"{}\"]", self.blue)?;
+        writeln!(f, "[Date \"{}\"]", self.date)?;
+        writeln!(
+            f,
+            "[Deal \"{}\"]",
+            deal.cards().map(|card| &*cards[card].name).join(", ")
+        )?;
+
+        if rules != Rules::default() {
+            writeln!(f, "[Variant \"{}\"]", rules)?;
+        }
+
+        if position != Game::with_cards(cards.clone(), deal, rules).position() {
+            writeln!(f, "[Position \"{}\"]", position)?;
+        }
+        writeln!(f, "[Result \"{}\"]", result)?;
+        writeln!(f)?;
+
+        let first = game.player();
+        for (ply, play) in self.game.history().plays().enumerate() {
+            let number = ply / 2 + 1;
+
+            if game.player() == first {
+                writeln!(f, "{}. {}", number, game.notation(play))?;
+            } else {
+                writeln!(f, "{}... {}", number, game.notation(play))?;
+            }
+            game.play(play);
+        }
+
+        writeln!(f, "{}", result)
+    }
+}
+
+const RESULTS: [&str; 5] = ["*", "red-won", "blue-won", "draw-timer", "draw-repetition"];
+
+/// The result of a game in `state`, `*` if unfinished.
+fn result(state: State) -> &'static str {
+    match state {
+        State::Turn(_) => "*",
+        State::Won(Red) => "red-won",
+        State::Won(Blue) => "blue-won",
+        State::Draw(DrawRule::Timer) => "draw-timer",
+        State::Draw(DrawRule::Repetition) => "draw-repetition",
+    }
+}
+
+/// Today's date (UTC) as `YYYY.MM.DD`.
+fn today() -> String {
+    let days = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs() / 86_400) as i64;
+
+    // Howard Hinnant's `civil_from_days`
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as i64;
+
+    format!("{:04}.{:02}.{:02}", year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(game: Game) {
+        let record = GameRecord::new(game);
+        let text = record.to_string();
+        let parsed = GameRecord::parse(&text, record.game.card_set()).unwrap();
+
+        assert_eq!(parsed.game.rules(), record.game.rules());
+        assert_eq!(parsed.game.state(), record.game.state());
+        assert_eq!(parsed.to_string(), text);
+    }
+
+    #[test]
+    fn discard_loss() {
+        let cards = Card::builtin();
+        let deal = Deal::parse("Frog,Dog,Tiger,Crab,Monkey", &cards).unwrap();
+        let rules = Rules {
+            discard: Discard::Loss,
+            draws: Draws::NEVER,
+            ..Rules::default()
+        };
+        // Red's king cannot move with Frog or Dog from a1
+        let mut game = GameBuilder::empty(cards, deal, rules)
+            .place(Square(A, One), Red, King)
+            .place(Square(C, Three), Blue, King)
+            .turn(Red)
+            .build()
+            .unwrap();
+
+        assert_eq!(game.play(Play::Discard(0)), State::Won(Blue));
+        round_trip(game);
+    }
+
+    #[test]
+    fn draws() {
+        let mut rng = Rng::new(Seed(4));
+
+        // Without winning rules, only the draw rule ends the games
+        for (draws, rule) in [
+            (Draws::timer(8), DrawRule::Timer),
+            (Draws::repetition(2), DrawRule::Repetition),
+        ] {
+            let rules = Rules {
+                stone: false,
+                stream: false,
+                draws,
+                ..Rules::default()
+            };
+            for _ in 0..20 {
+                let mut game = Game::random(Seed(rng.next_u64()), rules);
+                while game.player().is_some() {
+                    let plays = game.legal_plays();
+                    game.play(plays[rng.below(plays.len())]);
+                }
+
+                assert_eq!(game.state(), State::Draw(rule));
+                round_trip(game);
+            }
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use super::*;
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::str::FromStr;
+
+/// The rule that drew a game.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum DrawRule {
+    /// `Draws::timer` plies were played without a capture.
+    Timer,
+    /// The same position occurred `Draws::repetition` times.
+    Repetition,
+}
+
+/// When games are drawn. Both rules can be enabled at once, or none.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct Draws {
+    /// Draw after that many plies without a capture.
+    pub timer:      Option<u8>,
+    /// Draw when the same position occurs that many times.
+    pub repetition: Option<u8>,
+}
+
+impl Draws {
+    pub const NEVER: Self = Self {
+        timer:      None,
+        repetition: None,
+    };
+
+    pub fn timer(timer: u8) -> Self {
+        Self {
+            timer: Some(timer),
+            ..Self::NEVER
+        }
+    }
+
+    pub fn repetition(repetition: u8) -> Self {
+        Self {
+            repetition: Some(repetition),
+            ..Self::NEVER
+        }
+    }
+
+    pub fn both(timer: u8, repetition: u8) -> Self {
+        Self {
+            timer:      Some(timer),
+            repetition: Some(repetition),
+        }
+    }
+
+    /// The initial value of `Game::timer`.
+    pub fn limit(&self) -> u8 {
+        self.timer.unwrap_or(u8::MAX)
+    }
+}
+
+impl Default for Draws {
+    fn default() -> Self {
+        Self::timer(40)
+    }
+}
+
+/// What happens to a player that cannot move any piece.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Discard {
+    /// The player discards a card instead.
+    Allowed,
+    /// The player loses.
+    Loss,
+}
+
+/// The rules of a game.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct Rules {
+    /// Win by capturing the opponent's king (Way of the Stone).
+    pub stone:   bool,
+    /// Win by moving the king to the opponent's temple (Way of the Stream).
+    pub stream:  bool,
+    pub draws:   Draws,
+    pub discard: Discard,
+    /// Play with the Wind Spirit (Way of the Wind).
+    pub wind:    bool,
+    /// The player whose students are hidden ninjas (Light and Shadow).
+    pub shadow:  Option<Player>,
+    pub size:    Size,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            stone:   true,
+            stream:  true,
+            draws:   Draws::default(),
+            discard: Discard::Allowed,
+            wind:    false,
+            shadow:  None,
+            size:    Size::FIVE,
+        }
+    }
+}
+
+/// The rules that differ from the defaults, as words separated by spaces:
+/// `wind`, `shadow-red` or `shadow-blue`, `7x7`, `no-stone`, `no-stream`,
+/// `discard-loss`, `timer <plies>` (0 for none) and `repetition <n>`. Game
+/// records and engine programs get the rules this way.
+impl Display for Rules {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let default = Self::default();
+        let mut words = Vec::new();
+
+        if self.wind {
+            words.push(String::from("wind"));
+        }
+        match self.shadow {
+            Some(Red) => words.push(String::from("shadow-red")),
+            Some(Blue) => words.push(String::from("shadow-blue")),
+            None => {}
+        }
+        if self.size != default.size {
+            words.push(format!("{0}x{0}", self.size.get()));
+        }
+        if !self.stone {
+            words.push(String::from("no-stone"));
+        }
+        if !self.stream {
+            words.push(String::from("no-stream"));
+        }
+        if self.discard == Discard::Loss {
+            words.push(String::from("discard-loss"));
+        }
+        if self.draws.timer != default.draws.timer {
+            words.push(format!("timer {}", self.draws.timer.unwrap_or(0)));
+        }
+        if let Some(repetition) = self.draws.repetition {
+            words.push(format!("repetition {}", repetition));
+        }
+
+        write!(f, "{}", words.join(" "))
+    }
+}
+
+impl FromStr for Rules {
+    type Err = ();
+
+    fn from_str(str: &str) -> Result<Self, ()> {
+        let mut rules = Self::default();
+        let mut words = str.split_whitespace();
+        let number = |word: Option<&str>| word.and_then(|word| word.parse().ok()).ok_or(());
+
+        while let Some(word) = words.next() {
+            match word {
+                "wind" => rules.wind = true,
+                "shadow-red" => rules.shadow = Some(Red),
+                "shadow-blue" => rules.shadow = Some(Blue),
+                "no-stone" => rules.stone = false,
+                "no-stream" => rules.stream = false,
+                "discard-loss" => rules.discard = Discard::Loss,
+                "timer" =>
+                    rules.draws.timer = Some(number(words.next())?).filter(|&timer| timer > 0),
+                "repetition" => rules.draws.repetition = Some(number(words.next())?),
+                word => {
+                    let (files, ranks) = word.split_once('x').ok_or(())?;
+                    let size = files.parse::<usize>().map_err(|_| ())?;
+                    if ranks.parse() != Ok(size) {
+                        return Err(());
+                    }
+                    rules.size = Size::try_from(size)?;
+                }
+            }
+        }
+
+        Ok(rules)
+    }
+}
@@ -0,0 +1,102 @@
+use super::*;
+
+/// The width and height of the board: 5 for the base game, or 7 with seven
+/// students per side. Boards of any size use the same `Square`s and
+/// `Bitboard`s, those of a `MAX_SIZE` board, off-board squares left empty.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct Size(usize);
+
+impl Size {
+    pub const FIVE: Self = Self(5);
+    pub const SEVEN: Self = Self(7);
+
+    pub fn get(&self) -> usize {
+        self.0
+    }
+
+    pub fn files(&self) -> impl DoubleEndedIterator<Item = File> {
+        File::all().into_iter().take(self.0)
+    }
+
+    pub fn ranks(&self) -> impl DoubleEndedIterator<Item = Rank> {
+        Rank::all().into_iter().take(self.0)
+    }
+
+    pub fn squares(&self) -> impl Iterator<Item = Square> {
+        let ranks = *self;
+
+        self.files()
+            .flat_map(move |file| ranks.ranks().map(move |rank| Square(file, rank)))
+    }
+
+    /// The squares of the board.
+    pub fn area(&self) -> Bitboard {
+        let rank = (1 << self.0) - 1;
+
+        Bitboard((0..self.0).fold(0, |area, i| area | rank << (i * MAX_SIZE)))
+    }
+
+    /// The number of squares of the board.
+    pub fn squares_len(&self) -> usize {
+        self.0 * self.0
+    }
+
+    /// The index of `square` among the squares of the board, for tables.
+    pub fn index(&self, square: Square) -> usize {
+        square.rank() as usize * self.0 + square.file() as usize
+    }
+
+    /// The square of `index` (see `Size::index`).
+    pub fn square(&self, index: usize) -> Square {
+        Square(File::all()[index % self.0], Rank::all()[index / self.0])
+    }
+
+    /// `player`'s home rank.
+    pub fn home(&self, player: Player) -> Rank {
+        match player {
+            Red => Rank::all()[0],
+            Blue => Rank::all()[self.0 - 1],
+        }
+    }
+
+    /// `player`'s temple: their king's starting square.
+    pub fn temple(&self, player: Player) -> Square {
+        Square(King.file(*self), self.home(player))
+    }
+
+    pub fn center(&self) -> Square {
+        Square(File::all()[self.0 / 2], Rank::all()[self.0 / 2])
+    }
+
+    /// The pieces of each side, by home file.
+    pub fn pieces(&self) -> impl Iterator<Item = Piece> {
+        let size = *self;
+
+        self.files().map(move |file| {
+            if file == King.file(size) {
+                King
+            } else {
+                Piece::ALL[file as usize]
+            }
+        })
+    }
+}
+
+impl Default for Size {
+    fn default() -> Self {
+        Self::FIVE
+    }
+}
+
+/// Odd sizes from 5 to `MAX_SIZE`.
+impl TryFrom<usize> for Size {
+    type Error = ();
+
+    fn try_from(size: usize) -> Result<Self, ()> {
+        if (5..=MAX_SIZE).contains(&size) && size % 2 == 1 {
+            Ok(Self(size))
+        } else {
+            Err(())
+        }
+    }
+}
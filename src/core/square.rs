@@ -1,4 +1,8 @@
 use super::*;
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::str::FromStr;
 
 #[doc(hidden)]
 macro_rules! declare {
@@ -19,7 +23,8 @@ macro_rules! declare {
                 .ok()
             }
 
-            pub fn all() -> [Self; SIZE] {
+            /// Up to `MAX_SIZE` (see `Size` for those of a board).
+            pub fn all() -> [Self; MAX_SIZE] {
                 [$($V),*]
             }
         }
@@ -38,8 +43,8 @@ macro_rules! declare {
 }
 
 declare!(
-    File { 0 A   1 B   2 C     3 D    4 E    } horizontal: Horizontal   { Left Right }
-    Rank { 0 One 1 Two 2 Three 3 Four 4 Five } vertical:   Vertical     { Down Up    }
+    File { 0 A   1 B   2 C     3 D    4 E    5 F   6 G     } horizontal: Horizontal { Left Right }
+    Rank { 0 One 1 Two 2 Three 3 Four 4 Five 5 Six 6 Seven } vertical:   Vertical   { Down Up    }
 );
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
@@ -54,19 +59,53 @@ impl Square {
         self.1
     }
 
+    /// The index of this square in a `Bitboard`.
+    pub fn index(&self) -> usize {
+        self.1 as usize * MAX_SIZE + self.0 as usize
+    }
+
     pub fn apply(&self, r#move: Move) -> Option<Self> {
         Some(Self(self.0.apply(r#move.1)?, self.1.apply(r#move.0)?))
     }
+}
+
+impl From<usize> for Square {
+    fn from(index: usize) -> Self {
+        Self(
+            File::try_from(index % MAX_SIZE).unwrap(),
+            Rank::try_from(index / MAX_SIZE).unwrap(),
+        )
+    }
+}
 
-    pub fn king(player: Player) -> Self {
-        Self(C, player.rank())
+/// `a1`..`e5` (`g7` on a 7×7 board), Red's home rank being `1`.
+impl Display for Square {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}{}",
+            (b'a' + self.0 as u8) as char,
+            self.1 as usize + 1
+        )
     }
+}
+
+impl FromStr for Square {
+    type Err = ();
+
+    fn from_str(str: &str) -> Result<Self, ()> {
+        let mut chars = str.chars();
+        let file = chars.next().ok_or(())?;
+        let rank = chars.as_str().parse::<usize>().map_err(|_| ())?;
+
+        if !file.is_ascii_lowercase() || rank == 0 || chars.as_str().starts_with('+') {
+            return Err(());
+        }
 
-    pub fn all() -> impl Iterator<Item = Self> {
-        File::all()
-            .into_iter()
-            .map(|file| Rank::all().into_iter().map(move |rank| Square(file, rank)))
-            .flatten()
+        Ok(Self(
+            File::try_from((file as u8 - b'a') as usize)?,
+            Rank::try_from(rank - 1)?,
+        ))
     }
 }
 
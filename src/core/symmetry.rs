@@ -0,0 +1,93 @@
+//! Left-right mirror symmetry (files A↔E on a 5×5 board).
+//!
+//! A position and its mirror image, where each card is replaced by the card
+//! with mirrored moves (e.g. Frog and Rabbit, or Crab with itself), have the
+//! same outcome.
+
+use super::*;
+
+impl Square {
+    /// The square on the other side of the center file of a board of
+    /// `size`.
+    pub fn mirror(&self, size: Size) -> Self {
+        Self(File::all()[size.get() - 1 - self.0 as usize], self.1)
+    }
+}
+
+impl Play {
+    /// This play in the mirrored position of a board of `size`, its card
+    /// unchanged (see `Game::to_canonical` to mirror cards too).
+    pub fn mirror(&self, size: Size) -> Self {
+        match *self {
+            Self::Card { card, src, dest } => Self::Card {
+                card,
+                src: src.mirror(size),
+                dest: dest.mirror(size),
+            },
+            Self::Spirit { card, dest } => Self::Spirit {
+                card,
+                dest: dest.mirror(size),
+            },
+            Self::Wind {
+                card,
+                src,
+                dest,
+                spirit,
+            } => Self::Wind {
+                card,
+                src: src.mirror(size),
+                dest: dest.mirror(size),
+                spirit: spirit.mirror(size),
+            },
+            Self::Discard(card) => Self::Discard(card),
+        }
+    }
+
+    /// This play with `card` instead.
+    pub fn with_card(mut self, card: usize) -> Self {
+        match &mut self {
+            Self::Card { card: c, .. }
+            | Self::Spirit { card: c, .. }
+            | Self::Wind { card: c, .. }
+            | Self::Discard(c) => *c = card,
+        }
+        self
+    }
+}
+
+impl Card {
+    /// Whether `other` has the mirrored moves of this card.
+    pub fn is_mirror_of(&self, other: &Card) -> bool {
+        fn mirrored(moves: &[Move], others: &[Move]) -> bool {
+            let mirror = |r#move: &Move| {
+                let (file, rank) = r#move.offset();
+                (-file, rank)
+            };
+
+            moves.len() == others.len()
+                && moves
+                    .iter()
+                    .all(|r#move| others.iter().any(|other| other.offset() == mirror(r#move)))
+        }
+
+        mirrored(&self.moves, &other.moves) && mirrored(&self.spirit, &other.spirit)
+    }
+}
+
+impl Deal {
+    /// Each dealt card with its mirror image, if all mirror images are dealt.
+    pub fn mirrors(&self, cards: &[Card]) -> Option<[(usize, usize); Self::LEN]> {
+        let dealt = self.cards();
+        let mirror = |card: usize| {
+            dealt
+                .into_iter()
+                .find(|&other| cards[card].is_mirror_of(&cards[other]))
+        };
+
+        let mut mirrors = [(0, 0); Self::LEN];
+        for (i, card) in dealt.into_iter().enumerate() {
+            mirrors[i] = (card, mirror(card)?);
+        }
+        Some(mirrors)
+    }
+}
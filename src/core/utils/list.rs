@@ -43,6 +43,32 @@ impl<T, const N: usize> DerefMut for List<T, N> {
 }
 
 impl<T, const N: usize> List<T, N> {
+    pub const CAPACITY: usize = N;
+
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    pub fn push(&mut self, item: T) {
+        debug_assert!(!self.is_full());
+
+        self.items[self.len] = item;
+        self.len += 1;
+    }
+
+    pub fn pop(&mut self) -> Option<&T> {
+        if self.len == 0 {
+            None
+        } else {
+            self.len -= 1;
+            self.items.get(self.len)
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
     pub fn swap_remove(&mut self, index: usize) -> Option<&T> {
         debug_assert!(index < self.len());
 
@@ -56,3 +82,12 @@ impl<T, const N: usize> List<T, N> {
         }
     }
 }
+
+impl<'a, T, const N: usize> IntoIterator for &'a List<T, N> {
+    type IntoIter = std::slice::Iter<'a, T>;
+    type Item = &'a T;
+
+    fn into_iter(self) -> std::slice::Iter<'a, T> {
+        self.iter()
+    }
+}
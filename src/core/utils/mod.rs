@@ -1,3 +1,5 @@
 mod list;
+mod spill;
 
 pub use list::*;
+pub use spill::*;
@@ -0,0 +1,67 @@
+use super::*;
+use std::ops::Deref;
+use std::ops::DerefMut;
+
+/// A `List` that moves its items to the heap once more than `N` are pushed.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum SpillList<T, const N: usize> {
+    Inline(List<T, N>),
+    Heap(Vec<T>),
+}
+
+impl<T: Default, const N: usize> Default for SpillList<T, N> {
+    fn default() -> Self {
+        Self::Inline(List::default())
+    }
+}
+
+impl<T, const N: usize> Deref for SpillList<T, N> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        match self {
+            Self::Inline(list) => list,
+            Self::Heap(vec) => vec,
+        }
+    }
+}
+
+impl<T, const N: usize> DerefMut for SpillList<T, N> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        match self {
+            Self::Inline(list) => list,
+            Self::Heap(vec) => vec,
+        }
+    }
+}
+
+impl<T: Clone, const N: usize> SpillList<T, N> {
+    pub fn push(&mut self, item: T) {
+        match self {
+            Self::Inline(list) if !list.is_full() => list.push(item),
+            Self::Inline(list) => {
+                let mut vec = Vec::with_capacity(2 * N);
+                vec.extend_from_slice(list);
+                vec.push(item);
+                *self = Self::Heap(vec);
+            }
+            Self::Heap(vec) => vec.push(item),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        match self {
+            Self::Inline(list) => list.clear(),
+            Self::Heap(vec) => vec.clear(),
+        }
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a SpillList<T, N> {
+    type IntoIter = std::slice::Iter<'a, T>;
+    type Item = &'a T;
+
+    fn into_iter(self) -> std::slice::Iter<'a, T> {
+        self.iter()
+    }
+}
@@ -0,0 +1,68 @@
+//! Zobrist keys.
+//!
+//! Pawns are interchangeable, and so are the cards of a hand: keys only depend
+//! on what a position looks like.
+
+use super::*;
+
+const PIECES: [[[u64; SQUARES]; 2]; 2] = pieces();
+const BLUE: u64 = Rng::mix(1);
+const HANDS: u64 = 1 << 16;
+const SPARE: u64 = 2 << 16;
+const TIMER: u64 = 3 << 16;
+const SPIRIT: u64 = 4 << 16;
+const STATE: u64 = 5 << 16;
+const HIDDEN: u64 = 6 << 16;
+
+/// The key of `piece` of `player` on `square`.
+pub fn piece(player: Player, piece: Piece, square: Square) -> u64 {
+    PIECES[player.index()][(piece == King) as usize][square.index()]
+}
+
+/// The key of `card` (an index in `Game::card_set`) in `player`'s hand.
+pub fn hand(player: Player, card: usize) -> u64 {
+    Rng::mix(HANDS + (card * 2 + player.index()) as u64)
+}
+
+/// The key of `card` (an index in `Game::card_set`) as the spare card.
+pub fn spare(card: usize) -> u64 {
+    Rng::mix(SPARE + card as u64)
+}
+
+/// The key of `state`: none for Red to move, `Blue` to move and each end of
+/// the game their own.
+pub fn state(state: State) -> u64 {
+    match state {
+        State::Turn(Red) => 0,
+        State::Turn(Blue) => BLUE,
+        State::Won(player) => Rng::mix(STATE + player.index() as u64),
+        State::Draw(rule) => Rng::mix(STATE + 2 + rule as u64),
+    }
+}
+
+/// The key of the Wind Spirit on `square`.
+pub fn spirit(square: Square) -> u64 {
+    Rng::mix(SPIRIT + square.index() as u64)
+}
+
+/// The key of a hidden ninja on `square` (Light and Shadow).
+pub fn hidden(square: Square) -> u64 {
+    Rng::mix(HIDDEN + square.index() as u64)
+}
+
+/// The key of the draw timer.
+pub fn timer(timer: u8) -> u64 {
+    Rng::mix(TIMER + timer as u64)
+}
+
+const fn pieces() -> [[[u64; SQUARES]; 2]; 2] {
+    let mut keys = [[[0; SQUARES]; 2]; 2];
+    let mut i = 0;
+
+    while i < 2 * 2 * SQUARES {
+        keys[i / (2 * SQUARES)][i / SQUARES % 2][i % SQUARES] = Rng::mix(2 + i as u64);
+        i += 1;
+    }
+
+    keys
+}
@@ -0,0 +1,163 @@
+//! `onitama engine`: runs an engine over the Onitama Engine Interface on
+//! standard input and output (see `ai::protocol`).
+
+use super::*;
+use ai::Command;
+use ai::Reply;
+use args::EngineArgs;
+use args::GameArgs;
+use std::io::stdin;
+use std::io::stdout;
+use std::io::BufRead;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+pub fn main(args: impl Iterator<Item = String>) {
+    let args = Args::parse(args);
+    let cards = args.cards();
+    let tablebase = args.tablebase.as_ref().map(|path| {
+        ai::Tablebase::load(path, &cards)
+            .map(Arc::new)
+            .unwrap_or_else(|err| args::error(&format!("{}: {}", path.display(), err)))
+    });
+
+    let mut engine = args.engine.build(tablebase.as_ref());
+    let mut rules = Rules::default();
+    let mut start = None;
+    let mut game = None;
+
+    for line in stdin().lock().lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let reply = match line.parse() {
+            Err(()) => Some(Err(format!("unknown command: {}", line.trim()))),
+            Ok(Command::Oei) => {
+                println!("{}", Reply::Id(format!("onitama {}", engine.name())));
+                Some(Ok(Reply::OeiOk))
+            }
+            Ok(Command::IsReady) => Some(Ok(Reply::ReadyOk)),
+            Ok(Command::NewGame {
+                cards: names,
+                rules: new,
+            }) => match Deal::parse(&names.join(","), &cards) {
+                Some(deal) => {
+                    rules = new;
+                    start = Some(Game::with_cards(cards.clone(), deal, rules));
+                    game = None;
+                    engine = args.engine.build(tablebase.as_ref());
+                    None
+                }
+                None => Some(Err(format!("expected {} distinct card names", Deal::LEN))),
+            },
+            Ok(Command::Position { position, moves }) => {
+                match set_position(position, &moves, start.as_ref(), &cards, rules) {
+                    Ok(position) => {
+                        game = Some(position);
+                        None
+                    }
+                    Err(err) => {
+                        game = None;
+                        Some(Err(err))
+                    }
+                }
+            }
+            Ok(Command::Go(limits)) => match &game {
+                Some(game) if game.player().is_some() => Some(
+                    engine
+                        .play(game, &limits)
+                        .map(|play| Reply::BestMove(ai::to_protocol(&game.notation(play))))
+                        .map_err(|err| err.to_string()),
+                ),
+                Some(_) => Some(Err(String::from("game is over"))),
+                None => Some(Err(String::from("no position"))),
+            },
+            Ok(Command::Quit) => break,
+        };
+
+        match reply {
+            Some(Ok(reply)) => println!("{}", reply),
+            Some(Err(message)) => println!("{}", Reply::Error(message)),
+            None => {}
+        }
+        stdout().flush().unwrap();
+    }
+}
+
+/// The game of `position` (or `start` if `None`) after `moves`.
+fn set_position(
+    position: Option<String>,
+    moves: &[String],
+    start: Option<&Game>,
+    cards: &Cards,
+    rules: Rules,
+) -> Result<Game, String> {
+    let mut game = match position {
+        None => start.cloned().ok_or_else(|| String::from("no newgame"))?,
+        Some(position) => Position::parse(&position, cards)
+            .and_then(|position| Game::from_position(position, rules))
+            .map_err(|err| err.to_string())?,
+    };
+
+    for play in moves {
+        ai::from_protocol(play)
+            .and_then(|notation| game.play_of(&notation))
+            .map_err(|err| err.to_string())
+            .and_then(|parsed| game.try_play(parsed).map_err(|err| err.to_string()))
+            .map_err(|err| format!("{}: {}", play, err))?;
+    }
+
+    Ok(game)
+}
+
+struct Args {
+    engine:    EngineArgs,
+    cards:     Option<PathBuf>,
+    tablebase: Option<PathBuf>,
+}
+
+impl Args {
+    const USAGE: &'static str = "\
+Usage: onitama engine [options]
+    --engine <engine>           The engine to run: alphabeta (default) or mcts
+    --seed <hex>                Seed mcts with this
+    --cards <file>              Play with the cards of this card file
+    --tablebase <file>          Probe this tablebase (alphabeta)";
+
+    fn parse(mut args: impl Iterator<Item = String>) -> Self {
+        let mut engine = String::from("alphabeta");
+        let mut seed = Seed::random();
+        let mut cards = None;
+        let mut tablebase = None;
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--engine" => engine = args::value(args.next(), Self::USAGE),
+                "--seed" => seed = args::value(args.next(), Self::USAGE),
+                "--cards" => cards = Some(args::value(args.next(), Self::USAGE)),
+                "--tablebase" => tablebase = Some(args::value(args.next(), Self::USAGE)),
+                _ => args::error(Self::USAGE),
+            }
+        }
+
+        Self {
+            engine: EngineArgs::parse(&engine, seed).unwrap_or_else(|| args::error(Self::USAGE)),
+            cards,
+            tablebase,
+        }
+    }
+
+    /// `CARDS`, or the cards of `--cards`.
+    fn cards(&self) -> Cards {
+        GameArgs {
+            cards: self.cards.clone(),
+            ..GameArgs::default()
+        }
+        .cards()
+    }
+}
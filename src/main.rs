@@ -1,11 +1,25 @@
 #![allow(unused)]
 
+mod ai;
+mod args;
 mod core;
+mod engine;
+mod matches;
+mod perft;
+mod tablebase;
 mod term;
 
 pub use self::core::*;
 pub use term::*;
 
 fn main() {
-    term::main();
+    let mut args = std::env::args().skip(1).peekable();
+
+    match args.peek().map(String::as_str) {
+        Some("engine") => engine::main(args.skip(1)),
+        Some("match") => matches::main(args.skip(1)),
+        Some("perft") => perft::main(args.skip(1)),
+        Some("tablebase") => tablebase::main(args.skip(1)),
+        _ => term::main(args),
+    }
 }
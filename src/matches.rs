@@ -0,0 +1,511 @@
+//! `onitama match`: plays games between two engines, each deal twice with
+//! colours swapped, to tell whether the first is stronger.
+
+use super::*;
+use args::EngineArgs;
+use args::GameArgs;
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::time::Instant;
+
+/// The 95% confidence interval, in standard deviations.
+const Z95: f64 = 1.96;
+
+/// The random plies that open each deal's games from a fixed start (`--deal`
+/// or `--position`), so that they differ.
+const OPENING: usize = 2;
+
+pub fn main(args: impl Iterator<Item = String>) {
+    let args = Args::parse(args);
+    let games = args.games();
+    let mut score = Score::default();
+    let mut seeds = Rng::new(args.game.seed);
+
+    println!("First: {}", args.first.build(None).name());
+    println!("Second: {}", args.second.build(None).name());
+    println!("Limits: {}", args.limits);
+
+    for (number, (game, swap)) in games.enumerate() {
+        let first = if swap { Blue } else { Red };
+        let result = play(game.clone(), &args, first, Seed(seeds.next_u64()));
+        let cards = game
+            .position()
+            .deal
+            .cards()
+            .map(|card| &*game.card_set()[card].name);
+
+        score.add(result);
+        println!(
+            "Game {} ({}, first as {:?}): {}, score {}",
+            number + 1,
+            cards.join(","),
+            first,
+            match result {
+                Some(true) => "first wins",
+                Some(false) => "second wins",
+                None => "draw",
+            },
+            score,
+        );
+
+        if let Some(sprt) = &args.sprt {
+            if let Some(accepted) = sprt.test(&score) {
+                println!(
+                    "SPRT: {} accepted (LLR {:.2})",
+                    if accepted { "H1" } else { "H0" },
+                    sprt.llr(&score)
+                );
+                break;
+            }
+        }
+    }
+
+    println!();
+    println!("Games: {}", score.games());
+    println!("Score: {}", score);
+    match score.elo() {
+        Some((elo, margin)) => println!("Elo: {:+.1} ± {:.1}", elo, margin),
+        None => println!("Elo: -"),
+    }
+    if let Some(sprt) = &args.sprt {
+        let (lower, upper) = sprt.bounds();
+        println!(
+            "LLR: {:.2} ({:.2}, {:.2}) [{}, {}]",
+            sprt.llr(&score),
+            lower,
+            upper,
+            sprt.elo0,
+            sprt.elo1
+        );
+    }
+}
+
+/// Plays `game` to the end, the first engine playing `first` and engines
+/// seeded with `seed`: whether the first engine won (or the second
+/// forfeited), `None` for a draw.
+fn play(mut game: Game, args: &Args, first: Player, seed: Seed) -> Option<bool> {
+    let mut engines = [&args.first, &args.second].map(|engine| engine.with_seed(seed).build(None));
+    if first == Blue {
+        engines.reverse();
+    }
+    let mut clocks = [args.limits.clock; 2];
+
+    while let Some(player) = game.player() {
+        let clock = &mut clocks[player.index()];
+        let limits = ai::Limits {
+            clock: *clock,
+            ..args.limits
+        };
+        let start = Instant::now();
+
+        let engine = &mut engines[player.index()];
+        match engine.play(&game, &limits) {
+            Ok(play) => game.play(play),
+            // A failing engine program forfeits
+            Err(err) => {
+                println!("{} ({:?}) forfeits: {}", engine.name(), player, err);
+                return Some(player != first);
+            }
+        };
+        *clock = clock.map(|clock| clock.saturating_sub(start.elapsed()));
+    }
+
+    match game.state() {
+        State::Won(winner) => Some(winner == first),
+        _ => None,
+    }
+}
+
+/// The results of the first engine.
+#[derive(Copy, Clone, Default, Debug)]
+struct Score {
+    wins:   u32,
+    draws:  u32,
+    losses: u32,
+}
+
+impl Score {
+    fn add(&mut self, result: Option<bool>) {
+        match result {
+            Some(true) => self.wins += 1,
+            Some(false) => self.losses += 1,
+            None => self.draws += 1,
+        }
+    }
+
+    fn games(&self) -> u32 {
+        self.wins + self.draws + self.losses
+    }
+
+    /// The points per game, a draw counting half.
+    fn mean(&self) -> f64 {
+        (self.wins as f64 + self.draws as f64 / 2.0) / self.games() as f64
+    }
+
+    /// The variance of the points of a game.
+    fn variance(&self) -> f64 {
+        let mean = self.mean();
+        let games = self.games() as f64;
+
+        (self.wins as f64 * (1.0 - mean).powi(2)
+            + self.draws as f64 * (0.5 - mean).powi(2)
+            + self.losses as f64 * mean.powi(2))
+            / games
+    }
+
+    /// The Elo difference with its 95% error margin, if both engines scored.
+    fn elo(&self) -> Option<(f64, f64)> {
+        let mean = self.mean();
+        if !(0.0 < mean && mean < 1.0) {
+            return None;
+        }
+
+        let deviation = (self.variance() / self.games() as f64).sqrt();
+        let low = elo((mean - Z95 * deviation).max(f64::EPSILON));
+        let high = elo((mean + Z95 * deviation).min(1.0 - f64::EPSILON));
+
+        Some((elo(mean), (high - low) / 2.0))
+    }
+}
+
+impl Display for Score {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "+{} ={} -{}", self.wins, self.draws, self.losses)
+    }
+}
+
+/// A sequential probability ratio test of H0 (the first engine is `elo0`
+/// stronger) against H1 (`elo1` stronger).
+#[derive(Copy, Clone, Debug)]
+struct Sprt {
+    elo0:  f64,
+    elo1:  f64,
+    /// The rate of false positives (H1 accepted while H0 holds).
+    alpha: f64,
+    /// The rate of false negatives.
+    beta:  f64,
+}
+
+impl Sprt {
+    /// The log-likelihood ratio of H1 against H0, by the normal
+    /// approximation of the game points.
+    fn llr(&self, score: &Score) -> f64 {
+        let variance = score.variance();
+        if score.games() == 0 || variance == 0.0 {
+            return 0.0;
+        }
+
+        let (s0, s1) = (expected(self.elo0), expected(self.elo1));
+        score.games() as f64 * (s1 - s0) * (2.0 * score.mean() - s0 - s1) / (2.0 * variance)
+    }
+
+    /// The LLR under which H0 is accepted, and over which H1 is.
+    fn bounds(&self) -> (f64, f64) {
+        (
+            (self.beta / (1.0 - self.alpha)).ln(),
+            ((1.0 - self.beta) / self.alpha).ln(),
+        )
+    }
+
+    /// Whether H1 (`Some(true)`) or H0 (`Some(false)`) is accepted.
+    fn test(&self, score: &Score) -> Option<bool> {
+        let llr = self.llr(score);
+        let (lower, upper) = self.bounds();
+
+        if llr >= upper {
+            Some(true)
+        } else if llr <= lower {
+            Some(false)
+        } else {
+            None
+        }
+    }
+}
+
+/// The expected points per game of a player `elo` stronger.
+fn expected(elo: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf(-elo / 400.0))
+}
+
+/// The Elo difference of a player scoring `mean` points per game.
+fn elo(mean: f64) -> f64 {
+    -400.0 * (1.0 / mean - 1.0).log10()
+}
+
+struct Args {
+    game:   GameArgs,
+    first:  EngineArgs,
+    second: EngineArgs,
+    limits: ai::Limits,
+    games:  Option<usize>,
+    all:    bool,
+    sprt:   Option<Sprt>,
+}
+
+impl Args {
+    const USAGE: &'static str = "\
+Usage: onitama match [options]
+    --first <engine>            The engine tested: alphabeta (default), mcts or
+                                engine:<command> (an engine program)
+    --second <engine>           The engine it is tested against (default: alphabeta)
+    --games <n>                 How many games to play (default: 100, or two per deal
+                                with --deals all but neither --deal nor --position)
+    --deals <seeded|all>        Deal from --seed (default), or every set of five
+                                cards in turn (--deal and --position play only that,
+                                from random two-ply openings)
+    --sprt <elo0,elo1>          Stop once the first is found elo0 or elo1 stronger
+    --alpha <p>                 The SPRT false positive rate (default: 0.05)
+    --beta <p>                  The SPRT false negative rate (default: 0.05)";
+
+    fn parse(mut args: impl Iterator<Item = String>) -> Self {
+        let usage = format!(
+            "{}\n{}\n{}",
+            Self::USAGE,
+            args::LIMITS_USAGE,
+            GameArgs::USAGE
+        );
+        let mut game = GameArgs::default();
+        let mut first = String::from("alphabeta");
+        let mut second = String::from("alphabeta");
+        let mut limits = ai::Limits::default();
+        let mut games = None;
+        let mut all = false;
+        let mut sprt = None;
+        let mut alpha = 0.05;
+        let mut beta = 0.05;
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--first" => first = args::value(args.next(), &usage),
+                "--second" => second = args::value(args.next(), &usage),
+                "--games" => games = Some(args::value(args.next(), &usage)),
+                "--deals" =>
+                    all = match args::value::<String>(args.next(), &usage).as_str() {
+                        "seeded" => false,
+                        "all" => true,
+                        _ => args::error(&usage),
+                    },
+                "--sprt" => {
+                    let elos = args::value::<String>(args.next(), &usage);
+                    sprt = elos
+                        .split_once(',')
+                        .and_then(|(elo0, elo1)| Some((elo0.parse().ok()?, elo1.parse().ok()?)));
+                    if sprt.is_none() {
+                        args::error(&usage);
+                    }
+                }
+                "--alpha" => alpha = args::value(args.next(), &usage),
+                "--beta" => beta = args::value(args.next(), &usage),
+                arg =>
+                    if !args::parse_limits(&mut limits, arg, &mut args, &usage)
+                        && !game.parse(arg, &mut args, &usage)
+                    {
+                        args::error(&usage)
+                    },
+            }
+        }
+
+        let seed = game.seed;
+        let engine =
+            |name: &str| EngineArgs::parse(name, seed).unwrap_or_else(|| args::error(&usage));
+
+        Self {
+            first: engine(&first),
+            second: engine(&second),
+            game,
+            limits: args::or_default(limits),
+            games,
+            all,
+            sprt: sprt.map(|(elo0, elo1)| Sprt {
+                elo0,
+                elo1,
+                alpha,
+                beta,
+            }),
+        }
+    }
+
+    /// The games to play, with whether the first engine plays Blue.
+    fn games(&self) -> impl Iterator<Item = (Game, bool)> {
+        let rules = self.game.rules();
+        let cards = self.game.cards();
+        let dealt = self.game.dealt(&cards);
+        if Deal::random_from(self.game.seed, &dealt).is_none() {
+            args::error(&format!("at least {} cards needed", Deal::LEN));
+        }
+
+        let fixed = self.game.deal.is_some() || self.game.position.is_some();
+        let mut rng = Rng::new(self.game.seed);
+        let games: Box<dyn Iterator<Item = Game>> = if fixed {
+            let game = self.game.game();
+            Box::new(std::iter::repeat_with(move || opening(&game, &mut rng)))
+        } else if self.all {
+            Box::new(
+                combinations(dealt).map(move |[red0, red1, blue0, blue1, spare]| {
+                    let deal = Deal {
+                        red: [red0, red1],
+                        blue: [blue0, blue1],
+                        spare,
+                    };
+                    Game::with_cards(cards.clone(), deal, rules)
+                }),
+            )
+        } else {
+            Box::new(std::iter::repeat_with(move || {
+                let deal = Deal::random_from(Seed(rng.next_u64()), &dealt).unwrap();
+                Game::with_cards(cards.clone(), deal, rules)
+            }))
+        };
+
+        let len = match self.games {
+            Some(games) => games,
+            None if self.all && !fixed => usize::MAX,
+            None => 100,
+        };
+        games
+            .flat_map(|game| [(game.clone(), false), (game, true)])
+            .take(len)
+    }
+}
+
+/// `game` after `OPENING` random plies, none of which ends it (unless they
+/// all do).
+fn opening(game: &Game, rng: &mut Rng) -> Game {
+    let mut game = game.clone();
+
+    for _ in 0..OPENING {
+        let Some(player) = game.player() else {
+            break;
+        };
+        let mut plays = game.legal_plays().to_vec();
+        plays.retain(|&play| {
+            let mut next = game.clone();
+            next.play(play) == State::Turn(!player)
+        });
+        if plays.is_empty() {
+            break;
+        }
+        game.play(plays[rng.below(plays.len())]);
+    }
+
+    game
+}
+
+/// The sets of `Deal::LEN` of `cards`, in order.
+fn combinations(cards: Vec<usize>) -> impl Iterator<Item = [usize; Deal::LEN]> {
+    let n = cards.len();
+    let mut indices = (n >= Deal::LEN).then_some([0, 1, 2, 3, 4]);
+
+    std::iter::from_fn(move || {
+        let current = indices?;
+        let combination = current.map(|i| cards[i]);
+
+        // The next combination: bump the last index that can be
+        indices = (0..Deal::LEN)
+            .rev()
+            .find(|&i| current[i] < n - Deal::LEN + i)
+            .map(|i| {
+                let mut next = current;
+                next[i] += 1;
+                for j in i + 1..Deal::LEN {
+                    next[j] = next[j - 1] + 1;
+                }
+                next
+            });
+
+        Some(combination)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-6, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn elos() {
+        assert_close(expected(0.0), 0.5);
+        assert_close(expected(400.0), 10.0 / 11.0);
+        assert_close(expected(-400.0), 1.0 / 11.0);
+        assert_close(elo(0.5), 0.0);
+        assert_close(elo(0.75), 190.848501887865);
+        assert_close(elo(expected(123.0)), 123.0);
+    }
+
+    #[test]
+    fn score() {
+        let score = Score {
+            wins:   60,
+            draws:  20,
+            losses: 20,
+        };
+        let (elo, margin) = score.elo().unwrap();
+
+        assert_close(score.mean(), 0.7);
+        assert_close(score.variance(), 0.16);
+        assert_close(elo, 147.19071411783776);
+        assert_close(margin, 66.01463862816014);
+        assert_eq!(Score::default().elo(), None);
+    }
+
+    #[test]
+    fn sprt() {
+        let sprt = Sprt {
+            elo0:  0.0,
+            elo1:  10.0,
+            alpha: 0.05,
+            beta:  0.05,
+        };
+        let score = Score {
+            wins:   60,
+            draws:  20,
+            losses: 20,
+        };
+        let (lower, upper) = sprt.bounds();
+
+        assert_close(lower, -2.9444389791664403);
+        assert_close(upper, 2.9444389791664403);
+        assert_close(sprt.llr(&score), 1.7337133119290997);
+        assert_eq!(sprt.test(&score), None);
+        assert_eq!(sprt.llr(&Score::default()), 0.0);
+    }
+
+    #[test]
+    fn combinations() {
+        let cards = (0..16).collect::<Vec<_>>();
+        let all = super::combinations(cards.clone()).collect::<Vec<_>>();
+
+        assert_eq!(all.len(), 4368);
+        assert_eq!(all[0], [0, 1, 2, 3, 4]);
+        assert_eq!(all[all.len() - 1], [11, 12, 13, 14, 15]);
+        assert!(all.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(super::combinations(cards[..4].to_vec()).count(), 0);
+    }
+
+    #[test]
+    fn fixed_deal() {
+        let args = ["--deal", "Tiger,Crab,Monkey,Crane,Dragon", "--seed", "1"];
+        let args = Args::parse(args.into_iter().map(String::from));
+        let games = args.games().collect::<Vec<_>>();
+
+        assert_eq!(games.len(), 100);
+        for pair in games.chunks(2) {
+            assert_eq!(
+                pair[0].0.position().to_string(),
+                pair[1].0.position().to_string()
+            );
+            assert_eq!((pair[0].1, pair[1].1), (false, true));
+        }
+        let mut starts = games
+            .iter()
+            .map(|(game, _)| game.position().to_string())
+            .collect::<Vec<_>>();
+        starts.sort();
+        starts.dedup();
+        assert!(starts.len() > 10);
+    }
+}
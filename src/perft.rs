@@ -0,0 +1,71 @@
+//! `onitama perft`: counts the leaves of the game tree, to check move
+//! generation.
+
+use super::*;
+use args::GameArgs;
+use std::time::Instant;
+
+pub fn main(args: impl Iterator<Item = String>) {
+    let args = Args::parse(args);
+    let game = args.game.game();
+
+    println!("{}", game.position());
+
+    let start = Instant::now();
+    let nodes = if args.divide {
+        let mut nodes = 0;
+        for (play, count) in game.divide(args.depth) {
+            println!("{}: {}", game.notation(play), count);
+            nodes += count;
+        }
+        println!();
+        nodes
+    } else {
+        game.perft(args.depth)
+    };
+    let elapsed = start.elapsed();
+
+    println!("Nodes: {}", nodes);
+    println!(
+        "Time: {:.3}s ({:.0} nodes/s)",
+        elapsed.as_secs_f64(),
+        nodes as f64 / elapsed.as_secs_f64()
+    );
+}
+
+struct Args {
+    game:   GameArgs,
+    depth:  usize,
+    divide: bool,
+}
+
+impl Args {
+    const USAGE: &'static str = "\
+Usage: onitama perft <depth> [options]
+    --divide                    Count per root play";
+
+    fn parse(mut args: impl Iterator<Item = String>) -> Self {
+        let usage = format!("{}\n{}", Self::USAGE, GameArgs::USAGE);
+        let mut game = GameArgs::default();
+        let mut depth = None;
+        let mut divide = false;
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--divide" => divide = true,
+                arg if depth.is_none() && !arg.starts_with("--") =>
+                    depth = Some(args::value(Some(arg.to_string()), &usage)),
+                arg =>
+                    if !game.parse(arg, &mut args, &usage) {
+                        args::error(&usage)
+                    },
+            }
+        }
+
+        Self {
+            game,
+            depth: depth.unwrap_or_else(|| args::error(&usage)),
+            divide,
+        }
+    }
+}
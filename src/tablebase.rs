@@ -0,0 +1,108 @@
+//! `onitama tablebase`: solves the endgames of a deal by retrograde analysis.
+
+use super::*;
+use ai::Tablebase;
+use ai::Value;
+use args::GameArgs;
+use std::path::PathBuf;
+use std::time::Instant;
+
+pub fn main(args: impl Iterator<Item = String>) {
+    let args = Args::parse(args);
+    let game = args.game.game();
+    let rules = args.game.rules();
+    if rules.wind
+        || rules.shadow.is_some()
+        || !rules.stone
+        || !rules.stream
+        || rules.discard != Discard::Allowed
+    {
+        args::error("tablebases are for the base rules only");
+    }
+
+    let start = Instant::now();
+    let tablebase = Tablebase::generate(
+        game.card_set(),
+        game.position().deal,
+        game.size(),
+        args.pawns,
+    );
+    let elapsed = start.elapsed();
+
+    let names = tablebase.cards().each_ref().map(|card| &*card.name);
+    let (mut wins, mut losses, mut draws, mut longest) = (0, 0, 0, 0);
+    for value in tablebase.values() {
+        match value {
+            Value::Win(plies) => {
+                wins += 1;
+                longest = longest.max(plies);
+            }
+            Value::Loss(_) => losses += 1,
+            Value::Draw => draws += 1,
+        }
+    }
+
+    println!("Cards: {}", names.join(","));
+    println!("Board: {0}x{0}", game.size().get());
+    println!("Pawns: {}", args.pawns);
+    println!("Positions: {}", wins + losses + draws);
+    println!("Wins: {}", wins);
+    println!("Losses: {}", losses);
+    println!("Draws: {}", draws);
+    println!("Longest win: {} plies", longest);
+    println!("Time: {:.3}s", elapsed.as_secs_f64());
+
+    let path = args.output.unwrap_or_else(|| {
+        PathBuf::from(format!(
+            "{}-{}.otb",
+            names.join("-").to_lowercase(),
+            args.pawns
+        ))
+    });
+    match tablebase.save(&path) {
+        Ok(()) => println!("Tablebase: {}", path.display()),
+        Err(err) => args::error(&format!("{}: {}", path.display(), err)),
+    }
+}
+
+struct Args {
+    game:   GameArgs,
+    pawns:  usize,
+    output: Option<PathBuf>,
+}
+
+impl Args {
+    const USAGE: &'static str = "\
+Usage: onitama tablebase [options]
+    --pawns <0|1>               Pawns per side besides the kings (default: 1; two
+                                would take 2.3 GB on 5x5, and 7 GB to generate)
+    --output <file>             Save the tablebase there (default: <cards>-<pawns>.otb)";
+
+    fn parse(mut args: impl Iterator<Item = String>) -> Self {
+        let usage = format!("{}\n{}", Self::USAGE, GameArgs::USAGE);
+        let mut game = GameArgs::default();
+        let mut pawns = 1;
+        let mut output = None;
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--pawns" => pawns = args::value(args.next(), &usage),
+                "--output" => output = Some(args::value(args.next(), &usage)),
+                arg =>
+                    if !game.parse(arg, &mut args, &usage) {
+                        args::error(&usage)
+                    },
+            }
+        }
+
+        if pawns > ai::MAX_PAWNS {
+            args::error(&usage);
+        }
+
+        Self {
+            game,
+            pawns,
+            output,
+        }
+    }
+}
@@ -1,6 +1,9 @@
 mod ui;
 
 use super::*;
+use ai::Engine;
+use args::EngineArgs;
+use args::GameArgs;
 use ui::*;
 
 mod x {
@@ -31,64 +34,303 @@ mod x {
 
 use std::io::stdout;
 use std::io::Write;
-use std::process::exit;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::thread;
 use std::thread::sleep;
+use std::thread::JoinHandle;
 use std::time::Duration;
+use std::time::Instant;
+
+pub fn main(args: impl Iterator<Item = String>) {
+    let args = Args::parse(args);
+    let mut record = match &args.load {
+        Some(path) => GameRecord::load(path, &args.game.cards())
+            .unwrap_or_else(|err| args::error(&format!("{}: {}", path.display(), err))),
+        None => GameRecord::new(args.game.game()),
+    };
+
+    let tablebase = args.tablebase.as_ref().map(|path| {
+        ai::Tablebase::load(path, &args.game.cards())
+            .map(Arc::new)
+            .unwrap_or_else(|err| args::error(&format!("{}: {}", path.display(), err)))
+    });
+
+    let mut engines =
+        [args.red, args.blue].map(|engine| engine.map(|engine| engine.build(tablebase.as_ref())));
+    for (player, engine) in [Red, Blue].into_iter().zip(&engines) {
+        if let Some(engine) = engine {
+            let name = format!("{} ({})", engine.name(), args.limits);
+            match player {
+                Red => record.red = name,
+                Blue => record.blue = name,
+            }
+        }
+    }
 
-pub fn main() {
     enter();
-    game_loop();
+    let state = game_loop(
+        &mut record.game,
+        &mut engines,
+        args.limits,
+        tablebase.as_deref(),
+    );
     leave();
+
+    match state {
+        Some(State::Won(winner)) => println!("{:?} wins", winner),
+        Some(State::Draw(rule)) => println!("Draw ({:?})", rule),
+        _ => {}
+    }
+    println!("Seed: {}", args.game.seed);
+    println!("Position: {}", record.game.position());
+
+    if let Some(path) = &args.record {
+        match record.save(path) {
+            Ok(()) => println!("Record: {}", path.display()),
+            Err(err) => args::error(&format!("{}: {}", path.display(), err)),
+        }
+    }
+}
+
+struct Args {
+    game:      GameArgs,
+    load:      Option<PathBuf>,
+    record:    Option<PathBuf>,
+    red:       Option<EngineArgs>,
+    blue:      Option<EngineArgs>,
+    limits:    ai::Limits,
+    tablebase: Option<PathBuf>,
+}
+
+impl Args {
+    const USAGE: &'static str = "\
+Usage: onitama [options]
+       onitama perft <depth> [--divide] [options]
+       onitama tablebase [--pawns <n>] [--output <file>] [options]
+       onitama engine [options]
+       onitama match [--first <engine>] [--second <engine>] [options]
+    --load <file>               Continue the game of this record (rules included)
+    --record <file>             Save the game record there, finished or not (e.g. the
+                                --load file, to continue it later)
+    --red <player>              Who plays Red: human (default), alphabeta, mcts or
+                                engine:<command> (an engine program, e.g.
+                                \"engine:onitama engine --engine mcts\")
+    --blue <player>             Who plays Blue, likewise
+    --tablebase <file>          Probe this tablebase, for alphabeta and for hints (h)";
+
+    fn parse(mut args: impl Iterator<Item = String>) -> Self {
+        let usage = format!(
+            "{}\n{}\n{}",
+            Self::USAGE,
+            args::LIMITS_USAGE,
+            GameArgs::USAGE
+        );
+        let mut game = GameArgs::default();
+        let mut load = None;
+        let mut record = None;
+        let mut red = String::from("human");
+        let mut blue = String::from("human");
+        let mut limits = ai::Limits::default();
+        let mut tablebase = None;
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--load" => load = Some(args::value(args.next(), &usage)),
+                "--record" => record = Some(args::value(args.next(), &usage)),
+                "--red" => red = args::value(args.next(), &usage),
+                "--blue" => blue = args::value(args.next(), &usage),
+                "--tablebase" => tablebase = Some(args::value(args.next(), &usage)),
+                arg =>
+                    if !args::parse_limits(&mut limits, arg, &mut args, &usage)
+                        && !game.parse(arg, &mut args, &usage)
+                    {
+                        args::error(&usage)
+                    },
+            }
+        }
+
+        let seed = game.seed;
+        let engine = |name: &str| match name {
+            "human" => None,
+            name => Some(EngineArgs::parse(name, seed).unwrap_or_else(|| args::error(&usage))),
+        };
+
+        Self {
+            game,
+            load,
+            record,
+            red: engine(&red),
+            blue: engine(&blue),
+            limits: args::or_default(limits),
+            tablebase,
+        }
+    }
 }
 
-fn game_loop() {
+/// An engine thinking in the background, taken out of its slot until done.
+struct Thinking {
+    player: Player,
+    /// The position thought about, whose play is dropped if the game has
+    /// moved on meanwhile (undo, redo).
+    key:    u64,
+    ply:    usize,
+    start:  Instant,
+    handle: JoinHandle<(Box<dyn Engine>, Result<Play, EngineError>)>,
+}
+
+/// Runs `game` until it is over (`Some`) or the player leaves (`None`),
+/// `engines` playing for the players that have one, within `limits` (their
+/// clocks running only while they think).
+///
+/// Engines think in the background, so the interface stays responsive. With
+/// `tablebase`, `h` toggles hints of the best play on humans' turns.
+fn game_loop(
+    game: &mut Game,
+    engines: &mut [Option<Box<dyn Engine>>; 2],
+    limits: ai::Limits,
+    tablebase: Option<&ai::Tablebase>,
+) -> Option<State> {
+    // A loaded record may be of a finished game
+    if game.player().is_none() {
+        return Some(game.state());
+    }
+
     let spf = Duration::from_millis(100);
     let (width, height) = size();
+    let humans = engines.each_ref().map(Option::is_none);
+    let is_human = |game: &Game| game.player().is_some_and(|player| humans[player.index()]);
+    let mut clocks = [limits.clock; 2];
+    let mut thinking: Option<Thinking> = None;
+    let mut hints = false;
+    // Show the board as the humans see it, the one at the keyboard or, if
+    // both are, the one to play
+    let viewer = |game: &Game| match humans {
+        [true, true] => game.player(),
+        [true, false] => Some(Red),
+        [false, true] => Some(Blue),
+        [false, false] => None,
+    };
+    let game_ui = |game: &Game, hints: bool| {
+        let mut ui = GameUI::new(width, height, game.clone());
+        ui.set_viewer(viewer(game));
+        if hints && is_human(game) {
+            ui.set_hint(
+                tablebase
+                    .and_then(|tablebase| tablebase.best(game))
+                    .map(|(play, value)| (play, format!("{} ({})", game.notation(play), value))),
+            );
+        }
+        ui
+    };
 
-    let mut game = Game::new([8, 9], [10, 11], 12);
-    let mut ui = GameUI::new(width, height, game);
+    let mut ui = game_ui(game, hints);
     ui.render();
 
     loop {
         while let Some(event) = poll() {
-            match event {
-                x::Event::Key(event) =>
-                    if event.code == x::KeyCode::Esc {
-                        leave();
-                        exit(0);
-                    },
-                x::Event::Mouse(event) => {
-                    if event.kind == x::MouseEventKind::Down(x::MouseButton::Left) {
-                        if let Some(play) = ui
-                            .handle_click((event.column, event.row), |card, src, dest| {
-                                Play::Card { card, src, dest }
-                            })
-                        {
-                            match game.play(play) {
-                                State::Won(winner) => {
-                                    leave();
-                                    println!("{:?} wins", winner);
-                                    exit(0);
-                                }
-                                State::Draw => {
-                                    leave();
-                                    println!("Draw");
-                                    exit(0);
-                                }
-                                _ => {
-                                    ui = GameUI::new(width, height, game);
-                                    ui.render();
-                                }
-                            }
-                        }
+            let state = match event {
+                x::Event::Key(event) => match event.code {
+                    x::KeyCode::Esc => return None,
+                    // Undo the engines' plays too, back to a human's turn
+                    x::KeyCode::Char('u') => game.undo().map(|_| {
+                        while !is_human(game) && game.undo().is_some() {}
+                        game.state()
+                    }),
+                    x::KeyCode::Char('r') => game.redo(),
+                    x::KeyCode::Char('h') if tablebase.is_some() => {
+                        hints = !hints;
+                        Some(game.state())
                     }
-                }
+                    _ => None,
+                },
+                x::Event::Mouse(event) =>
+                    if event.kind == x::MouseEventKind::Down(x::MouseButton::Left) && is_human(game)
+                    {
+                        ui.handle_click((event.column, event.row))
+                            .map(|play| game.play(play))
+                    } else {
+                        None
+                    },
                 x::Event::Resize(width, height) => {
                     ui.set_size(width, height);
                     ui.render();
+                    None
+                }
+            };
+
+            match state {
+                Some(State::Turn(_)) => {
+                    ui = game_ui(game, hints);
+                    ui.render();
+                }
+                Some(state) => return Some(state),
+                None => {}
+            }
+        }
+
+        if thinking
+            .as_ref()
+            .is_some_and(|thinking| thinking.handle.is_finished())
+        {
+            let Thinking {
+                player,
+                key,
+                ply,
+                start,
+                handle,
+            } = thinking.take().unwrap();
+            let (engine, play) = handle.join().unwrap_or_else(|panic| {
+                let message = panic
+                    .downcast_ref::<String>()
+                    .map(String::as_str)
+                    .or_else(|| panic.downcast_ref::<&str>().copied())
+                    .unwrap_or("engine failed");
+
+                leave();
+                args::error(message)
+            });
+            let play = play.unwrap_or_else(|err| {
+                leave();
+                args::error(&format!("{}: {}", engine.name(), err))
+            });
+            let clock = &mut clocks[player.index()];
+
+            *clock = clock.map(|clock| clock.saturating_sub(start.elapsed()));
+            engines[player.index()] = Some(engine);
+
+            if game.key() == key && game.history().len() == ply {
+                match game.play(play) {
+                    State::Turn(_) => {
+                        ui = game_ui(game, hints);
+                        ui.render();
+                    }
+                    state => return Some(state),
                 }
             }
         }
+
+        if let Some(player) = game.player() {
+            if let Some(mut engine) = engines[player.index()].take() {
+                let position = game.clone();
+                let limits = ai::Limits {
+                    clock: clocks[player.index()],
+                    ..limits
+                };
+
+                thinking = Some(Thinking {
+                    player,
+                    key: game.key(),
+                    ply: game.history().len(),
+                    start: Instant::now(),
+                    handle: thread::spawn(move || {
+                        let play = engine.play(&position, &limits);
+                        (engine, play)
+                    }),
+                });
+            }
+        }
+
         sleep(spf);
         stdout().flush().unwrap();
     }
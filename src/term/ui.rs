@@ -4,6 +4,8 @@ use x::Stylize;
 
 const KING: &str = "♔";
 const PAWN: &str = "♙";
+const NINJA: &str = "♟";
+const SPIRIT: &str = "◎";
 const V: char = '│';
 const H: char = '─';
 const TL: char = '╭';
@@ -17,6 +19,7 @@ const HBL: char = '┕';
 const HBR: char = '┙';
 const RED: x::Color = x::Rgb { r: 255, g: 0, b: 0 };
 const BLUE: x::Color = x::Rgb { r: 0, g: 0, b: 255 };
+const GREEN: x::Color = x::Rgb { r: 0, g: 160, b: 0 };
 const BLACK: x::Color = x::Rgb { r: 0, g: 0, b: 0 };
 const WHITE: x::Color = x::Rgb {
     r: 180,
@@ -28,6 +31,7 @@ const TINT: u8 = 75;
 enum State {
     Card(usize),
     Square(usize, Square, Vec<Square>),
+    Spirit(usize, Square, Square, Vec<Square>),
 }
 
 pub struct GameUI {
@@ -35,21 +39,63 @@ pub struct GameUI {
     height: u16,
     game:   Game,
     state:  Option<State>,
+    /// A play to suggest, with its description.
+    hint:   Option<(Play, String)>,
+    /// Whose view of the board to show (Light and Shadow), `None` for the
+    /// whole board.
+    viewer: Option<Player>,
 }
 
 impl GameUI {
-    const BOARD_HEIGHT: u16 = Self::BOARD_SQUARE_HEIGHT * SIZE as u16;
     const BOARD_SQUARE_HEIGHT: u16 = 3;
     const BOARD_SQUARE_WIDTH: u16 = 6;
-    const BOARD_WIDTH: u16 = Self::BOARD_SQUARE_WIDTH * SIZE as u16;
-    const CARD_HEIGHT: u16 = Self::CARD_SQUARE_HEIGHT * SIZE as u16 + 3;
     const CARD_SQUARE_HEIGHT: u16 = 1;
     const CARD_SQUARE_WIDTH: u16 = 3;
-    const CARD_WIDTH: u16 = Self::CARD_SQUARE_WIDTH * SIZE as u16 + 2;
-    const HAND_WIDTH: u16 = 2 * Self::CARD_WIDTH + Self::MARGIN;
-    const HEIGHT: u16 = Self::BOARD_HEIGHT + 2 * (Self::CARD_HEIGHT + Self::MARGIN);
     const MARGIN: u16 = 1;
-    const WIDTH: u16 = Self::BOARD_WIDTH + 2 * (Self::CARD_WIDTH + Self::MARGIN);
+
+    fn board_height(&self) -> u16 {
+        Self::BOARD_SQUARE_HEIGHT * self.size()
+    }
+
+    fn board_width(&self) -> u16 {
+        Self::BOARD_SQUARE_WIDTH * self.size()
+    }
+
+    fn card_height(&self) -> u16 {
+        Self::CARD_SQUARE_HEIGHT * self.card_size() + 3
+    }
+
+    fn card_width(&self) -> u16 {
+        Self::CARD_SQUARE_WIDTH * self.card_size() + 2
+    }
+
+    fn hand_width(&self) -> u16 {
+        2 * self.card_width() + Self::MARGIN
+    }
+
+    fn size(&self) -> u16 {
+        self.game.size().get() as u16
+    }
+
+    /// The width and height of the cards' grids: the board's, or more for
+    /// cards reaching further.
+    fn card_size(&self) -> u16 {
+        let game = &self.game;
+        let reach = game
+            .cards(Red)
+            .into_iter()
+            .chain(game.cards(Blue))
+            .chain([game.spare()])
+            .flat_map(|card| card.moves.iter().chain(card.spirit.iter()))
+            .map(|mov| {
+                let (file, rank) = mov.offset();
+                file.unsigned_abs().max(rank.unsigned_abs()) as u16
+            })
+            .max()
+            .unwrap_or(0);
+
+        self.size().max(2 * reach + 1)
+    }
 }
 
 impl GameUI {
@@ -57,34 +103,58 @@ impl GameUI {
         Self {
             width,
             height,
+            viewer: game.player(),
             game,
             state: None,
+            hint: None,
         }
     }
 
+    pub fn set_viewer(&mut self, viewer: Option<Player>) {
+        self.viewer = viewer;
+    }
+
+    pub fn set_hint(&mut self, hint: Option<(Play, String)>) {
+        self.hint = hint;
+    }
+
     pub fn set_size(&mut self, width: u16, height: u16) {
         self.width = width;
         self.height = height;
     }
 
-    pub fn handle_click<T>(
-        &mut self,
-        pos: (u16, u16),
-        f: impl FnOnce(usize, Square, Square) -> T,
-    ) -> Option<T> {
+    pub fn handle_click(&mut self, pos: (u16, u16)) -> Option<Play> {
         fn it_contains(mut it: impl Iterator<Item = Square>, square: Square) -> bool {
             it.find(|&s| s == square).is_some()
         }
 
+        let player = self.game.player().unwrap();
         let rect_contains = |(x1, y1, x2, y2), (x, y)| x1 <= x && x <= x2 && y1 <= y && y <= y2;
         let state_square =
             |card, src| State::Square(card, src, self.game.dests(card, src).collect::<Vec<_>>());
+        let state_spirit = |card, src, dest| {
+            State::Spirit(
+                card,
+                src,
+                dest,
+                self.game.spirit_dests(card, src, dest).collect::<Vec<_>>(),
+            )
+        };
+        let play = |card, src, dest| {
+            if self.game.spirit() == Some(src) {
+                Play::Spirit { card, dest }
+            } else {
+                Play::Card { card, src, dest }
+            }
+        };
+        let is_src = |card, src| {
+            it_contains(self.game[player].squares(), src)
+                || (self.game.spirit() == Some(src) && !self.game.is_wind(card))
+        };
 
-        let player = self.game.player().unwrap();
         let board = self.board_rect();
         let [pc0, pc1] = self.cards_rect(player);
         let [oc0, oc1] = self.cards_rect(!player);
-        let pieces = self.game[player].squares();
 
         enum Clicked {
             Square(Square),
@@ -93,7 +163,9 @@ impl GameUI {
 
         let clicked = if rect_contains(board, pos) {
             Some(Clicked::Square(
-                Square::all()
+                self.game
+                    .size()
+                    .squares()
                     .find(|&square| rect_contains(self.square_rect(square), pos))
                     .unwrap(),
             ))
@@ -116,17 +188,31 @@ impl GameUI {
             },
             Some(State::Card(card)) => match clicked {
                 Some(Clicked::Card(p, card)) if p == player => Some(State::Card(card)),
-                Some(Clicked::Square(src)) if it_contains(pieces, src) =>
-                    Some(state_square(*card, src)),
+                Some(Clicked::Square(src)) if is_src(*card, src) => Some(state_square(*card, src)),
                 Some(Clicked::Square(_)) => Some(State::Card(*card)),
                 _ => None,
             },
             Some(State::Square(card, src, dests)) => match clicked {
                 Some(Clicked::Card(p, card)) if p == player => Some(State::Card(card)),
-                Some(Clicked::Square(src)) if it_contains(pieces, src) =>
-                    Some(state_square(*card, src)),
-                Some(Clicked::Square(dest)) if it_contains(dests.iter().copied(), dest) =>
-                    return Some(f(*card, *src, dest)),
+                Some(Clicked::Square(src)) if is_src(*card, src) => Some(state_square(*card, src)),
+                Some(Clicked::Square(dest)) if dests.contains(&dest) =>
+                    if self.game.is_wind(*card) {
+                        Some(state_spirit(*card, *src, dest))
+                    } else {
+                        return Some(play(*card, *src, dest));
+                    },
+                Some(Clicked::Square(_)) => Some(State::Card(*card)),
+                _ => None,
+            },
+            Some(State::Spirit(card, src, dest, spirits)) => match clicked {
+                Some(Clicked::Card(p, card)) if p == player => Some(State::Card(card)),
+                Some(Clicked::Square(spirit)) if spirits.contains(&spirit) =>
+                    return Some(Play::Wind {
+                        card: *card,
+                        src: *src,
+                        dest: *dest,
+                        spirit,
+                    }),
                 Some(Clicked::Square(_)) => Some(State::Card(*card)),
                 _ => None,
             },
@@ -143,6 +229,7 @@ impl GameUI {
         self.clear(lock);
         self.render_board(lock);
         self.render_cards(lock);
+        self.render_hint(lock);
 
         lock.flush().unwrap();
     }
@@ -154,8 +241,8 @@ impl GameUI {
     }
 
     fn render_cards(&self, lock: &mut StdoutLock) {
-        let red = self.game[Red].cards();
-        let blue = self.game[Blue].cards();
+        let red = self.game.cards(Red);
+        let blue = self.game.cards(Blue);
 
         let [(rx1, ry1, ..), (rx2, ry2, ..)] = self.cards_rect(Red);
         let [(bx1, by1, ..), (bx2, by2, ..)] = self.cards_rect(Blue);
@@ -167,6 +254,7 @@ impl GameUI {
             None => None,
             Some(State::Card(card)) => Some(card),
             Some(State::Square(card, ..)) => Some(card),
+            Some(State::Spirit(card, ..)) => Some(card),
         };
 
         let is_selected = |p, card| selected == Some(card) && player == p;
@@ -181,7 +269,7 @@ impl GameUI {
     fn render_card(
         &self,
         lock: &mut StdoutLock,
-        card: Card,
+        card: &Card,
         x: u16,
         y: u16,
         player: Player,
@@ -189,8 +277,8 @@ impl GameUI {
     ) {
         self.render_card_borders(lock, x, y, player, is_selected);
 
-        let ranks = [Five, Four, Three, Two, One];
-        let files = [A, B, C, D, E];
+        let size = self.card_size();
+        let center = (size / 2) as isize;
         let x = x + 1;
         let y = y + 1;
         let board_y = if player == Red { y + 1 } else { y };
@@ -201,22 +289,26 @@ impl GameUI {
                 write!(lock, "{}", card.name).unwrap();
             } else {
                 let len = card.name.len();
-                to(lock, x + 3 * SIZE as u16 - len as u16, y + SIZE as u16);
+                to(lock, x + 3 * size - len as u16, y + size);
 
-                for char in reverse(card.name) {
+                for char in reverse(&card.name) {
                     write!(lock, "{}", char).unwrap();
                 }
             }
         };
 
         let board = |lock: &mut StdoutLock| {
-            for rank in ranks {
-                let y = board_y + SIZE as u16 - rank as u16 - 1;
+            for rank in (0..size).rev() {
+                let y = board_y + size - rank - 1;
 
                 to(lock, x, y);
-                for file in files {
-                    let center = if (file, rank) == (C, Three) { "*" } else { " " };
-                    let bg = bg(file, rank);
+                for file in 0..size {
+                    let center = if (file, rank) == (size / 2, size / 2) {
+                        "*"
+                    } else {
+                        " "
+                    };
+                    let bg = bg(file as usize, rank as usize);
                     let fg = match player {
                         Red => RED,
                         Blue => BLUE,
@@ -229,21 +321,26 @@ impl GameUI {
             }
         };
 
+        // The Wind Spirit's moves of wind cards in green
         let moves = |lock: &mut StdoutLock| {
-            for mov in card.moves {
+            let moves = card.moves.iter().map(|mov| (mov, "*", None));
+            let spirit = card.spirit.iter().map(|mov| (mov, SPIRIT, Some(GREEN)));
+
+            for (mov, mark, color) in moves.chain(spirit) {
                 let mut mov = *mov;
                 if player == Blue {
                     mov.flip()
                 };
 
-                let Square(file, rank) = Square(C, Three).apply(mov).unwrap();
-                let bg = bg(file, rank);
-                let fg = if bg == WHITE { BLACK } else { WHITE };
-                let x = x + 3 * file as u16 + 1;
-                let y = board_y + SIZE as u16 - rank as u16 - 1;
+                let (file, rank) = mov.offset();
+                let (file, rank) = ((center + file) as u16, (center + rank) as u16);
+                let bg = bg(file as usize, rank as usize);
+                let fg = color.unwrap_or(if bg == WHITE { BLACK } else { WHITE });
+                let x = x + 3 * file + 1;
+                let y = board_y + size - rank - 1;
 
                 to(lock, x, y);
-                write!(lock, "{}", "*".with(fg).on(bg)).unwrap();
+                write!(lock, "{}", mark.with(fg).on(bg)).unwrap();
             }
         };
 
@@ -269,19 +366,20 @@ impl GameUI {
             x::Color::Reset
         };
 
+        let size = self.card_size();
         let line = |lock: &mut StdoutLock, y, l: char, m: char, r: char| {
             to(lock, x, y);
             write!(lock, "{}", l.with(color)).unwrap();
-            for _ in 0..3 * SIZE {
+            for _ in 0..3 * size {
                 write!(lock, "{}", m.with(color)).unwrap();
             }
             write!(lock, "{}", r.with(color)).unwrap();
         };
 
         let body = |lock: &mut StdoutLock, y| {
-            for i in 0..=SIZE as u16 {
+            for i in 0..=size {
                 let y = y + i;
-                let x2 = x + 3 * SIZE as u16 + 1;
+                let x2 = x + 3 * size + 1;
                 let v = V.with(color);
                 write!(lock, "{}{}{}{}", x::MoveTo(x, y), v, x::MoveTo(x2, y), v).unwrap();
             }
@@ -290,24 +388,34 @@ impl GameUI {
         if player == Red {
             line(lock, y, TL, H, TR);
             body(lock, y + 1);
-            line(lock, y + 2 + SIZE as u16, HBL, HH, HBR);
+            line(lock, y + 2 + size, HBL, HH, HBR);
         } else {
             line(lock, y, HTL, HH, HTR);
             body(lock, y + 1);
-            line(lock, y + 2 + SIZE as u16, BL, H, BR);
+            line(lock, y + 2 + size, BL, H, BR);
+        }
+    }
+
+    fn render_hint(&self, lock: &mut StdoutLock) {
+        if let Some((_, hint)) = &self.hint {
+            to(lock, 0, 0);
+            write!(lock, "Hint: {}", hint).unwrap();
         }
     }
 
     fn render_board(&self, lock: &mut StdoutLock) {
-        let x = (self.width - Self::BOARD_WIDTH) / 2;
-        let y = (self.height - Self::BOARD_HEIGHT) / 2;
-        let size = SIZE as u16;
-        let ranks = [Five, Four, Three, Two, One];
-        let files = [A, B, C, D, E];
+        let x = (self.width - self.board_width()) / 2;
+        let y = (self.height - self.board_height()) / 2;
+        let size = self.size();
+        let ranks = self.game.size().ranks().rev();
+        let files = || self.game.size().files();
         let player = self.game.player().unwrap();
+        let view = self
+            .viewer
+            .map_or(self.game.position().board, |viewer| self.game.view(viewer));
 
-        let tinted_bg = |file, rank| {
-            let bg = bg(file, rank);
+        let tinted_bg = |file: File, rank: Rank| {
+            let bg = bg(file as usize, rank as usize);
 
             if self.is_active(Square(file, rank)) {
                 tint(bg, player)
@@ -320,19 +428,26 @@ impl GameUI {
             let y = y + 3 * (size - 1 - rank as u16);
 
             to(lock, x, y);
-            for file in files {
+            for file in files() {
                 write!(lock, "{}", "      ".on(tinted_bg(file, rank))).unwrap();
             }
 
             to(lock, x, y + 1);
-            for file in files {
+            for file in files() {
                 let bg = tinted_bg(file, rank);
-                let center = match self.game[Square(file, rank)] {
+                let square = Square(file, rank);
+                let pawn = if self.game.hidden().contains(square) {
+                    NINJA
+                } else {
+                    PAWN
+                };
+                let center = match view[square] {
+                    None if self.game.spirit() == Some(square) => SPIRIT.with(GREEN),
                     None => " ".on(bg),
                     Some((Red, King)) => KING.with(RED),
                     Some((Blue, King)) => KING.with(BLUE),
-                    Some((Red, _)) => PAWN.with(RED),
-                    Some((Blue, _)) => PAWN.with(BLUE),
+                    Some((Red, _)) => pawn.with(RED),
+                    Some((Blue, _)) => pawn.with(BLUE),
                 };
 
                 write!(lock, "{}", "  ".on(bg)).unwrap();
@@ -341,7 +456,7 @@ impl GameUI {
             }
 
             to(lock, x, y + 2);
-            for file in files {
+            for file in files() {
                 write!(lock, "{}", "      ".on(tinted_bg(file, rank))).unwrap();
             }
         }
@@ -350,53 +465,59 @@ impl GameUI {
 
 impl GameUI {
     fn is_active(&self, square: Square) -> bool {
-        if let Some(State::Square(_, src, dests)) = &self.state {
-            square == *src || dests.contains(&square)
-        } else {
-            false
+        match &self.state {
+            Some(State::Square(_, src, dests)) => square == *src || dests.contains(&square),
+            Some(State::Spirit(_, src, dest, spirits)) =>
+                square == *src || square == *dest || spirits.contains(&square),
+            // The hint shows until a card is selected
+            Some(State::Card(_)) => false,
+            None => match self.hint {
+                Some((Play::Card { src, dest, .. }, _)) => square == src || square == dest,
+                _ => false,
+            },
         }
     }
 
     fn board_rect(&self) -> (u16, u16, u16, u16) {
-        let x = (self.width - Self::BOARD_WIDTH) / 2;
-        let y = (self.height - Self::BOARD_HEIGHT) / 2;
+        let x = (self.width - self.board_width()) / 2;
+        let y = (self.height - self.board_height()) / 2;
 
-        (x, y, x + Self::BOARD_WIDTH, y + Self::BOARD_HEIGHT)
+        (x, y, x + self.board_width(), y + self.board_height())
     }
 
     fn cards_rect(&self, player: Player) -> [(u16, u16, u16, u16); HAND] {
         let (_, board_y1, _, board_y2) = self.board_rect();
-        let x1 = (self.width - Self::HAND_WIDTH) / 2;
-        let x2 = x1 + Self::MARGIN + Self::CARD_WIDTH;
+        let x1 = (self.width - self.hand_width()) / 2;
+        let x2 = x1 + Self::MARGIN + self.card_width();
         let y = if player == Red {
             board_y2 + Self::MARGIN
         } else {
-            board_y1 - Self::MARGIN - Self::CARD_HEIGHT
+            board_y1 - Self::MARGIN - self.card_height()
         };
 
         [
-            (x1, y, x1 + Self::CARD_WIDTH, y + Self::CARD_HEIGHT),
-            (x2, y, x2 + Self::CARD_WIDTH, y + Self::CARD_HEIGHT),
+            (x1, y, x1 + self.card_width(), y + self.card_height()),
+            (x2, y, x2 + self.card_width(), y + self.card_height()),
         ]
     }
 
     fn spare_rect(&self) -> (u16, u16, u16, u16) {
         let (board_x1, _, board_x2, _) = self.board_rect();
-        let y = (self.height - Self::CARD_HEIGHT) / 2;
+        let y = (self.height - self.card_height()) / 2;
         let x = if self.game.player().unwrap() == Red {
             board_x2 + Self::MARGIN
         } else {
-            board_x1 - Self::MARGIN - Self::CARD_WIDTH
+            board_x1 - Self::MARGIN - self.card_width()
         };
 
-        (x, y, x + Self::CARD_WIDTH, y + Self::CARD_HEIGHT)
+        (x, y, x + self.card_width(), y + self.card_height())
     }
 
     fn square_rect(&self, square: Square) -> (u16, u16, u16, u16) {
         let (board_x, board_y, ..) = self.board_rect();
 
         let file = square.file() as u16;
-        let rank = SIZE as u16 - square.rank() as u16 - 1;
+        let rank = self.size() - square.rank() as u16 - 1;
 
         let x = board_x + file * Self::BOARD_SQUARE_WIDTH;
         let y = board_y + rank * Self::BOARD_SQUARE_HEIGHT;
@@ -410,8 +531,8 @@ impl GameUI {
     }
 }
 
-fn bg(file: File, rank: Rank) -> x::Color {
-    if (file as usize + rank as usize) % 2 == 0 {
+fn bg(file: usize, rank: usize) -> x::Color {
+    if (file + rank).is_multiple_of(2) {
         BLACK
     } else {
         WHITE
@@ -447,11 +568,9 @@ const REVERSED_UPPER: &[char] = &[
 ];
 
 fn reverse(str: &str) -> impl '_ + Iterator<Item = char> {
-    str.bytes().rev().map(|byte| {
-        if byte <= b'Z' {
-            REVERSED_UPPER[(byte - b'A') as usize]
-        } else {
-            REVERSED_LOWER[(byte - b'a') as usize]
-        }
+    str.bytes().rev().map(|byte| match byte {
+        b'A'..=b'Z' => REVERSED_UPPER[(byte - b'A') as usize],
+        b'a'..=b'z' => REVERSED_LOWER[(byte - b'a') as usize],
+        _ => byte as char,
     })
 }